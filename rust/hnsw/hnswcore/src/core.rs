@@ -89,6 +89,34 @@ where
     }
 }
 
+// SearchStats
+// cost of a kann search, the greedy path from the top layer to layer 0
+#[derive(Debug, Default, Clone)]
+pub struct SearchStats {
+    pub visited: usize,          // visited nodes of all layers
+    pub distances: usize,        // distance computations of all layers
+    pub layers: Vec<LayerStats>, // top layer first
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct LayerStats {
+    pub layer: usize,
+    pub enterpoint: String, // node the layer search starts from
+    pub nearest: String,    // nearest node found, the enterpoint of the next layer
+    pub ef: usize,
+    pub visited: usize,
+    pub distances: usize,
+    pub usec: u128,
+}
+
+impl SearchStats {
+    fn add(&mut self, layer: LayerStats) {
+        self.visited += layer.visited;
+        self.distances += layer.distances;
+        self.layers.push(layer);
+    }
+}
+
 type NodeRef<T> = Arc<RwLock<_Node<T>>>;
 type NodeRefWeak<T> = Weak<RwLock<_Node<T>>>;
 
@@ -482,7 +510,25 @@ where
             return Ok(Vec::new());
         }
 
-        Ok(self.search_kann_internal(data, k, self.ef_construction))
+        Ok(self.search_kann_internal(data, k, self.ef_construction, None))
+    }
+
+    // search_kann with the search cost of each layer
+    pub fn search_kann_profile(
+        &self,
+        data: &[T],
+        k: usize,
+    ) -> Result<(Vec<SearchResult<T, R>>, SearchStats), HNSWError> {
+        if data.len() != self.data_dim {
+            return Err(format!("data dimension: {} does not match Index", data.len()).into());
+        }
+        let mut stats = SearchStats::default();
+        if self.enterpoint.is_none() || self.node_count == 0 {
+            return Ok((Vec::new(), stats));
+        }
+
+        let res = self.search_kann_internal(data, k, self.ef_construction, Some(&mut stats));
+        Ok((res, stats))
     }
 
     // perform insertion of new nodes into the index
@@ -610,6 +656,18 @@ where
         ep: &Node<T>,
         ef: usize,
         level: usize,
+    ) -> BinaryHeap<SimPair<T, R>> {
+        self.search_level_stats(query, ep, ef, level, &mut LayerStats::default())
+    }
+
+    // search_level counting visited nodes and distance computations
+    fn search_level_stats(
+        &self,
+        query: &[T],
+        ep: &Node<T>,
+        ef: usize,
+        level: usize,
+        stats: &mut LayerStats,
     ) -> BinaryHeap<SimPair<T, R>> {
         let mut v = HashSet::with_capacity(ef);
 
@@ -622,6 +680,8 @@ where
         }
         let qpair = SimPair::new(qsim, ep.clone());
 
+        stats.distances += 1;
+
         let mut c = BinaryHeap::with_capacity(ef);
         let mut w = BinaryHeap::with_capacity(ef);
         c.push(qpair.clone());
@@ -654,6 +714,7 @@ where
                         &neighbor.read().data,
                         self.data_dim,
                     ));
+                    stats.distances += 1;
                     if esim > fpair.0.read().sim || w.len() < ef {
                         let epair = SimPair::new(esim, neighbor.clone());
                         c.push(epair.clone());
@@ -667,6 +728,8 @@ where
             }
         }
 
+        stats.visited += v.len();
+
         let mut res = BinaryHeap::new();
         for pair in w {
             res.push(pair.0);
@@ -862,32 +925,60 @@ where
         updated
     }
 
-    fn search_kann_internal(&self, query: &[T], k: usize, ef: usize) -> Vec<SearchResult<T, R>> {
+    fn search_kann_internal(
+        &self,
+        query: &[T],
+        k: usize,
+        ef: usize,
+        mut stats: Option<&mut SearchStats>,
+    ) -> Vec<SearchResult<T, R>> {
         let mut ep = self.enterpoint.as_ref().unwrap().clone();
         let l_max = self.max_layer;
 
         let mut lc = l_max;
-        while lc > 0 {
-            let w = self.search_level(query, &ep.upgrade(), 1, lc);
+        loop {
+            let ef = if lc > 0 { 1 } else { ef };
+            let start = std::time::Instant::now();
+            let mut layer = LayerStats {
+                layer: lc,
+                ef,
+                ..Default::default()
+            };
+            let w = self.search_level_stats(query, &ep.upgrade(), ef, lc, &mut layer);
+            if let Some(stats) = stats.as_mut() {
+                layer.usec = start.elapsed().as_micros();
+                layer.enterpoint = short_name(&ep.upgrade().read().name);
+                layer.nearest = match w.peek() {
+                    Some(pair) => short_name(&pair.read().node.read().name),
+                    None => String::new(),
+                };
+                stats.add(layer);
+            }
+            if lc == 0 {
+                return self.collect_results(w, k);
+            }
             ep = w.peek().unwrap().read().node.downgrade();
             lc -= 1;
         }
+    }
 
-        let mut w = self.search_level(query, &ep.upgrade(), ef, 0);
-
+    fn collect_results(
+        &self,
+        mut w: BinaryHeap<SimPair<T, R>>,
+        k: usize,
+    ) -> Vec<SearchResult<T, R>> {
         let mut res = Vec::with_capacity(k);
         while res.len() < k && !w.is_empty() {
             let c = w.pop().unwrap();
             let cr = c.read();
             let cnr = cr.node.read();
-            res.push(SearchResult::new(
-                cr.sim,
-                &((&cnr.name).split('.').collect::<Vec<&str>>())
-                    .last()
-                    .unwrap(),
-                &cnr.data,
-            ));
+            res.push(SearchResult::new(cr.sim, &short_name(&cnr.name), &cnr.data));
         }
         res
     }
 }
+
+// node name without the index name prefix, e.g. n1 of hnsw.{idx0}.n1
+fn short_name(name: &str) -> String {
+    name.rsplit('.').next().unwrap_or(name).to_string()
+}
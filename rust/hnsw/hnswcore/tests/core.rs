@@ -79,3 +79,45 @@ fn hnsw_test() {
         assert_eq!(sc, 1);
     }
 }
+
+#[test]
+fn hnsw_search_profile_test() {
+    let n = 100;
+    let data_dim = 4;
+    let mut index: Index<f32, f32> = Index::new("foo", Box::new(euclidean), data_dim, 5, 16);
+    let mock_fn = |_s: String, _n: Node<f32>| {};
+    for i in 0..n {
+        let name = format!("foo.node{}", i);
+        let data = vec![i as f32; data_dim];
+        index.add_node(&name, &data, mock_fn).unwrap();
+    }
+
+    // profile returns the same results as search with the cost of each layer
+    let query = vec![10.0; 4];
+    let res = index.search_kann(&query, 5).unwrap();
+    let (profiled, stats) = index.search_kann_profile(&query, 5).unwrap();
+    assert_eq!(
+        res.iter().map(|r| r.name.as_str()).collect::<Vec<&str>>(),
+        profiled
+            .iter()
+            .map(|r| r.name.as_str())
+            .collect::<Vec<&str>>()
+    );
+    assert_eq!(profiled[0].name.as_str(), "node10");
+    assert_eq!(stats.layers.len(), index.max_layer + 1);
+    assert_eq!(stats.layers.last().unwrap().layer, 0);
+    assert_eq!(stats.layers.last().unwrap().ef, index.ef_construction);
+    assert_eq!(stats.layers.last().unwrap().nearest.as_str(), "node10");
+    for (upper, lower) in stats.layers.iter().zip(stats.layers.iter().skip(1)) {
+        assert_eq!(upper.nearest, lower.enterpoint);
+    }
+    assert!(stats.visited >= stats.layers.len());
+    assert!(stats.distances >= stats.visited);
+    assert!(stats.visited <= n * stats.layers.len());
+
+    // empty index
+    let empty: Index<f32, f32> = Index::new("bar", Box::new(euclidean), data_dim, 5, 16);
+    let (res, stats) = empty.search_kann_profile(&query, 5).unwrap();
+    assert!(res.is_empty());
+    assert!(stats.layers.is_empty());
+}
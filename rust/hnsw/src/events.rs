@@ -0,0 +1,578 @@
+use std::collections::{HashMap, HashSet};
+use std::os::raw::{c_int, c_void};
+use std::sync::Mutex;
+
+use redis_module::{
+    raw, CallOptionsBuilder, CallResult, Context, ContextFlags, NotifyEvent, RedisError,
+    RedisString, Status,
+};
+
+use crate::types::*;
+use crate::{config, registry, stats};
+use crate::{get_selected_db, INDICES, PREFIX};
+
+lazy_static! {
+    // MOVE fires move_from in the source db before move_to in the target db,
+    // index key name -> source db, node keys are moved on move_to event.
+    static ref PENDING_MOVES: Mutex<HashMap<String, i32>> = Mutex::new(HashMap::new());
+    // COPY REPLACE unlinks the dst index after the src index is copied and before copy_to,
+    // (db, dst index key name) -> node names of the replaced dst index.
+    static ref PENDING_COPIES: Mutex<HashMap<(i32, String), Vec<String>>> = Mutex::new(HashMap::new());
+    // RENAME fires rename_from before rename_to, the source key name of the renamed node key.
+    static ref PENDING_RENAME: Mutex<Option<String>> = Mutex::new(None);
+    // (db, index key name) moved back by a rolled back RENAME/MOVE, its node keys aren't moved.
+    static ref ROLLBACKS: Mutex<HashSet<(i32, String)>> = Mutex::new(HashSet::new());
+}
+
+// copying
+// the index is copied to the dst key in db, the dst key maybe replaced
+pub fn copying(db: i32, name: &str) {
+    PENDING_COPIES
+        .lock()
+        .unwrap()
+        .insert((db, name.to_string()), Vec::new());
+}
+
+// unlinked
+// the index key is unlinked, the node names of a replaced COPY dst index are kept,
+// so its node keys are deleted on copy_to.
+pub fn unlinked(db: i32, name: &str, index: &IndexRedis) {
+    if let Some(nodes) = PENDING_COPIES
+        .lock()
+        .unwrap()
+        .get_mut(&(db, name.to_string()))
+    {
+        *nodes = index.nodes.clone();
+    }
+}
+
+// add_post_notification_job
+// keys can't be written in keyspace notifications, the job writes them atomically with the cmd
+// after the notification, its writes are replicated with the cmd.
+// note: replicas and loading don't run jobs, they apply the replicated writes of the master,
+// the module doesn't load without post notification jobs (redis < 7.2).
+fn add_post_notification_job(
+    ctx: &Context,
+    event: &str,
+    key_name: &str,
+    job: impl FnOnce(&Context) -> Result<(), RedisError> + 'static,
+) {
+    let (event, key_name) = (event.to_string(), key_name.to_string());
+    let res = ctx.add_post_notification_job(move |ctx| {
+        if let Err(e) = job(ctx) {
+            ctx.log_warning(format!("{} Index: {} err {}", event, key_name, e).as_str());
+        }
+    });
+    if !matches!(res, Ok(Status::Ok))
+        && !ctx
+            .get_flags()
+            .intersects(ContextFlags::SLAVE | ContextFlags::LOADING)
+    {
+        ctx.log_warning(
+            format!(
+                "{} Index: {} add post notification job err",
+                event, key_name
+            )
+            .as_str(),
+        );
+    }
+}
+
+// add_rename_job
+// the job renames the value of the key renamed from from_name, see rename_value,
+// and replicates the rename by hnsw.key.rename.
+fn add_rename_job(ctx: &Context, db: i32, key_name: &str, from_name: String) {
+    let to_name = key_name.to_string();
+    add_post_notification_job(ctx, "rename_to", key_name, move |ctx| {
+        select_db(ctx, db)?;
+        if rename_value(ctx, &to_name, &from_name)? {
+            ctx.replicate(
+                &format!("{}.key.rename", PREFIX),
+                &[to_name.as_str(), from_name.as_str()],
+            );
+        }
+        Ok(())
+    });
+}
+
+// rename_value
+// the index value of the renamed index key is renamed as the key name with its node names,
+// neighbors of the node value of a node key renamed with its index link the renamed index nodes.
+// return false if the key isn't an index or node key, e.g. it's renamed again by a rollback.
+pub fn rename_value(ctx: &Context, key_name: &str, from_name: &str) -> Result<bool, RedisError> {
+    let rkey = ctx.open_key_writable(&ctx.create_string(key_name));
+    if let Ok(Some(node)) = rkey.get_value::<NodeRedis>(&HNSW_NODE_REDIS_TYPE) {
+        return match renamed_index_names(from_name, key_name) {
+            Some((from_index, to_index)) => {
+                *node = node.renamed(from_index, to_index);
+                Ok(true)
+            }
+            None => Ok(false),
+        };
+    }
+    match rkey.get_value::<IndexRedis>(&HNSW_INDEX_REDIS_TYPE) {
+        Ok(Some(index)) => {
+            *index = index.renamed(key_name);
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+// call the write cmd, it's replicated
+fn call_replicated(ctx: &Context, cmd: &str, args: &[&str]) -> Result<(), RedisError> {
+    let opts = CallOptionsBuilder::new().replicate().build();
+    let res: CallResult = ctx.call_ext(cmd, &opts, args);
+    res.map(|_| ()).map_err(RedisError::from)
+}
+
+// notify_keyspace_event
+// fire the module keyspace event of a write cmd on the key, e.g. hnsw.node.add,
+// subscribers need the d class of notify-keyspace-events, e.g. CONFIG SET notify-keyspace-events KEd,
+// config notify_events no disables them.
+pub fn notify_keyspace_event(ctx: &Context, event: &str, key_name: &str) {
+    if !config::notify_events() {
+        return;
+    }
+    ctx.notify_keyspace_event(NotifyEvent::MODULE, event, &ctx.create_string(key_name));
+}
+
+// on_keyspace_event
+// keep the index registry in sync with keyspace changes which aren't made by module cmds,
+// and the index identity (name, node keys, cached graph) follows the index key.
+pub fn on_keyspace_event(ctx: &Context, _event_type: NotifyEvent, event: &str, key: &[u8]) {
+    if !is_hnsw_key(key) {
+        // the renamed key isn't a node key
+        if event == "rename_from" {
+            PENDING_RENAME.lock().unwrap().take();
+        }
+        return;
+    }
+    let db = get_selected_db(ctx);
+    let key_name = String::from_utf8_lossy(key).to_string();
+    // ttl changes don't change the value
+    if !matches!(event, "expire" | "persist") {
+        invalidate(db, &key_name);
+    }
+    match event {
+        // key is removed from db
+        "del" | "expired" | "evicted" => {
+            registry::remove(db, &key_name);
+        }
+        "rename_from" => {
+            registry::remove(db, &key_name);
+            *PENDING_RENAME.lock().unwrap() = Some(key_name);
+        }
+        "move_from" => {
+            if registry::remove(db, &key_name) {
+                PENDING_MOVES.lock().unwrap().insert(key_name, db);
+            }
+        }
+        // index value is moved to the key, node keys follow the index key,
+        // values are renamed and node keys are moved by post notification jobs.
+        "rename_to" | "move_to" => {
+            let from_name = match event {
+                "rename_to" => PENDING_RENAME.lock().unwrap().take(),
+                _ => None,
+            };
+            let rkey = ctx.open_key(&RedisString::create_from_slice(ctx.ctx, key));
+            if let Ok(Some(_)) = rkey.get_value::<NodeRedis>(&HNSW_NODE_REDIS_TYPE) {
+                // node key renamed with its index, neighbors link the renamed index nodes
+                if let Some(from_name) = from_name {
+                    add_rename_job(ctx, db, &key_name, from_name);
+                }
+                return;
+            }
+            match rkey.get_value::<IndexRedis>(&HNSW_INDEX_REDIS_TYPE) {
+                Ok(Some(index)) => {
+                    registry::add(db, &key_name);
+                    let from_db = match event {
+                        "rename_to" => Some(db),
+                        _ => PENDING_MOVES.lock().unwrap().remove(&key_name),
+                    };
+                    let from_name = index.name.clone();
+                    if event == "rename_to" {
+                        add_rename_job(ctx, db, &key_name, from_name.clone());
+                    }
+                    if ROLLBACKS.lock().unwrap().remove(&(db, key_name.clone())) {
+                        return;
+                    }
+                    if let Some(from_db) = from_db {
+                        let nodes_move = NodesMove {
+                            index_name: key_name.clone(),
+                            nodes: index
+                                .nodes
+                                .iter()
+                                .map(|n| rename_node(n, &from_name, &key_name))
+                                .collect(),
+                            from_db,
+                            from_name,
+                            to_db: db,
+                        };
+                        add_post_notification_job(ctx, event, &key_name, move |ctx| {
+                            nodes_move.run(ctx)
+                        });
+                    }
+                }
+                _ => {
+                    registry::remove(db, &key_name);
+                }
+            }
+        }
+        "copy_to" => {
+            let replaced = PENDING_COPIES
+                .lock()
+                .unwrap()
+                .remove(&(db, key_name.clone()));
+            let rkey = ctx.open_key(&RedisString::create_from_slice(ctx.ctx, key));
+            match rkey.get_value::<IndexRedis>(&HNSW_INDEX_REDIS_TYPE) {
+                Ok(Some(index)) => {
+                    registry::add(db, &key_name);
+                    if let Some((from_db, from_name)) = index.copy_from.clone() {
+                        let copy = NodesCopy {
+                            index_name: key_name.clone(),
+                            nodes: index.nodes.clone(),
+                            from_db,
+                            from_name,
+                            to_db: db,
+                            replaced: replaced.unwrap_or_default(),
+                        };
+                        add_post_notification_job(ctx, event, &key_name, move |ctx| copy.run(ctx));
+                    }
+                }
+                _ => {
+                    registry::remove(db, &key_name);
+                }
+            }
+        }
+        // key value is set, check whether it is an index
+        "restore" | "loaded" => {
+            let rkey = ctx.open_key(&RedisString::create_from_slice(ctx.ctx, key));
+            match rkey.get_value::<IndexRedis>(&HNSW_INDEX_REDIS_TYPE) {
+                Ok(Some(_)) => registry::add(db, &key_name),
+                _ => {
+                    registry::remove(db, &key_name);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+// on_overwrite_event
+// index or node key is overwritten by a value of another type, e.g. SET hnsw.{idx0} v
+// note: the events of all keys of the data types are fired, keys which aren't hnsw keys are skipped first.
+pub fn on_overwrite_event(ctx: &Context, _event_type: NotifyEvent, _event: &str, key: &[u8]) {
+    if !is_hnsw_key(key) {
+        return;
+    }
+    let db = get_selected_db(ctx);
+    let key_name = String::from_utf8_lossy(key).to_string();
+    invalidate(db, &key_name);
+    if registry::contains(db, &key_name) {
+        registry::remove(db, &key_name);
+    }
+}
+
+// invalidate
+// remove the cached graph of the index key, or of the index which owns the node key, in db
+fn invalidate(db: i32, key_name: &str) {
+    let owns = |index_name: &str| match key_name.strip_prefix(index_name) {
+        Some(short_name) => short_name.is_empty() || short_name.starts_with('.'),
+        None => false,
+    };
+
+    if !INDICES
+        .read()
+        .unwrap()
+        .keys()
+        .any(|(d, name)| *d == db && owns(name))
+    {
+        return;
+    }
+    INDICES
+        .write()
+        .unwrap()
+        .retain(|(d, name), _| *d != db || !owns(name));
+}
+
+fn select_db(ctx: &Context, db: i32) -> Result<(), RedisError> {
+    let res = unsafe { raw::RedisModule_SelectDb.unwrap()(ctx.ctx, db) };
+    if res != raw::REDISMODULE_OK as c_int {
+        return Err(RedisError::String(format!("select db {} err", db)));
+    }
+    Ok(())
+}
+
+// NodesCopy
+// copy node keys of the source index (from_db, from_name) to the copied index in to_db
+struct NodesCopy {
+    index_name: String,
+    nodes: Vec<String>,
+    from_db: i32,
+    from_name: String,
+    to_db: i32,
+    replaced: Vec<String>, // node names of the dst index replaced by COPY REPLACE
+}
+
+impl NodesCopy {
+    // node keys are copied by replicated COPY cmds, the node copy callback renames the neighbors.
+    // the copied index is deleted if a source node key doesn't exist or isn't hash tagged,
+    // so COPY doesn't leave an index without node keys.
+    fn run(self, ctx: &Context) -> Result<(), RedisError> {
+        // the copy source is only kept until the job, replicas don't run it and don't save copy_from
+        select_db(ctx, self.to_db)?;
+        let key = ctx.open_key_writable(&ctx.create_string(self.index_name.as_str()));
+        if let Ok(Some(index)) = key.get_value::<IndexRedis>(&HNSW_INDEX_REDIS_TYPE) {
+            index.copy_from = None;
+        }
+        let res = self.copy(ctx);
+        select_db(ctx, self.to_db)?;
+        if res.is_err() {
+            call_replicated(ctx, "DEL", &[self.index_name.as_str()])?;
+        }
+        res
+    }
+
+    fn copy(&self, ctx: &Context) -> Result<(), RedisError> {
+        // the node keys of the replaced index which aren't overwritten
+        select_db(ctx, self.to_db)?;
+        let nodes = self.nodes.iter().collect::<HashSet<&String>>();
+        for node_name in self.replaced.iter().filter(|n| !nodes.contains(n)) {
+            let nkey = ctx.open_key(&ctx.create_string(node_name.as_str()));
+            if let Ok(Some(_)) = nkey.get_value::<NodeRedis>(&HNSW_NODE_REDIS_TYPE) {
+                call_replicated(ctx, "DEL", &[node_name.as_str()])?;
+            }
+        }
+
+        // check all source node keys before copying
+        select_db(ctx, self.from_db)?;
+        let mut from_node_names = Vec::with_capacity(self.nodes.len());
+        for node_name in &self.nodes {
+            let from_node_name = rename_node(node_name, &self.index_name, &self.from_name);
+            if node_index_name(&from_node_name) != Some(self.from_name.as_str())
+                || node_index_name(node_name) != Some(self.index_name.as_str())
+            {
+                return Err(RedisError::String(format!(
+                    "Node: {} isn't hash tagged, migrate the index before COPY",
+                    from_node_name
+                )));
+            }
+            let nkey = ctx.open_key(&ctx.create_string(from_node_name.as_str()));
+            match nkey.get_value::<NodeRedis>(&HNSW_NODE_REDIS_TYPE) {
+                Ok(Some(_)) => from_node_names.push(from_node_name),
+                _ => return Err(crate::errors::no_node(from_node_name)),
+            }
+        }
+
+        let to_db = self.to_db.to_string();
+        for (from_node_name, node_name) in from_node_names.iter().zip(self.nodes.iter()) {
+            call_replicated(
+                ctx,
+                "COPY",
+                &[
+                    from_node_name.as_str(),
+                    node_name.as_str(),
+                    "DB",
+                    to_db.as_str(),
+                    "REPLACE",
+                ],
+            )?;
+        }
+        Ok(())
+    }
+}
+
+// NodesMove
+// move node keys of the index renamed or moved from (from_db, from_name) to the index key in to_db
+struct NodesMove {
+    index_name: String,
+    nodes: Vec<String>, // node names of the index key
+    from_db: i32,
+    from_name: String,
+    to_db: i32,
+}
+
+impl NodesMove {
+    // node keys are moved by replicated RENAME or MOVE cmds, the node rename_to jobs rename the neighbors.
+    // if a source node key doesn't exist or the target node key exists, the moved node keys
+    // and the index key are moved back, so node keys are never overwritten or left behind.
+    fn run(self, ctx: &Context) -> Result<(), RedisError> {
+        let mut moved = Vec::new();
+        let res = self.moves(ctx, &mut moved);
+        if res.is_err() {
+            self.rollback(ctx, &moved)?;
+        }
+        res
+    }
+
+    // moved are the (source, target) names of the moved node keys
+    fn moves(&self, ctx: &Context, moved: &mut Vec<(String, String)>) -> Result<(), RedisError> {
+        // check all node keys before moving
+        let mut from_node_names = Vec::with_capacity(self.nodes.len());
+        for node_name in &self.nodes {
+            let from_node_name = rename_node(node_name, &self.index_name, &self.from_name);
+            select_db(ctx, self.from_db)?;
+            let nkey = ctx.open_key(&ctx.create_string(from_node_name.as_str()));
+            match nkey.get_value::<NodeRedis>(&HNSW_NODE_REDIS_TYPE) {
+                Ok(Some(_)) => {}
+                _ => return Err(crate::errors::no_node(from_node_name)),
+            }
+            select_db(ctx, self.to_db)?;
+            if !ctx
+                .open_key(&ctx.create_string(node_name.as_str()))
+                .is_null()
+            {
+                return Err(RedisError::String(format!(
+                    "Node: {} already exists in db {}",
+                    node_name, self.to_db
+                )));
+            }
+            from_node_names.push(from_node_name);
+        }
+
+        select_db(ctx, self.from_db)?;
+        for (from_node_name, node_name) in from_node_names.into_iter().zip(self.nodes.iter()) {
+            self.move_key(ctx, &from_node_name, node_name, self.to_db)?;
+            moved.push((from_node_name, node_name.clone()));
+        }
+        Ok(())
+    }
+
+    // rollback
+    // move the moved node keys back, and then the index key, the index key moved back doesn't move its node keys.
+    fn rollback(&self, ctx: &Context, moved: &[(String, String)]) -> Result<(), RedisError> {
+        select_db(ctx, self.to_db)?;
+        for (from_node_name, node_name) in moved.iter().rev() {
+            self.move_key(ctx, node_name, from_node_name, self.from_db)?;
+        }
+        ROLLBACKS
+            .lock()
+            .unwrap()
+            .insert((self.from_db, self.from_name.clone()));
+        self.move_key(ctx, &self.index_name, &self.from_name, self.from_db)
+    }
+
+    // RENAME the key in the selected db, or MOVE it to db with the same name
+    fn move_key(&self, ctx: &Context, from: &str, to: &str, db: i32) -> Result<(), RedisError> {
+        if self.from_db == self.to_db {
+            call_replicated(ctx, "RENAME", &[from, to])
+        } else {
+            call_replicated(ctx, "MOVE", &[from, db.to_string().as_str()])
+        }
+    }
+}
+
+unsafe extern "C" fn on_flush_event(
+    _ctx: *mut raw::RedisModuleCtx,
+    _eid: raw::RedisModuleEvent,
+    subevent: u64,
+    data: *mut c_void,
+) {
+    if subevent != raw::REDISMODULE_SUBEVENT_FLUSHDB_END as u64 {
+        return;
+    }
+    let fi = &*(data as *const raw::RedisModuleFlushInfoV1);
+    registry::flush(fi.dbnum);
+    INDICES
+        .write()
+        .unwrap()
+        .retain(|(db, _), _| fi.dbnum != -1 && *db != fi.dbnum);
+}
+
+unsafe extern "C" fn on_swapdb_event(
+    _ctx: *mut raw::RedisModuleCtx,
+    _eid: raw::RedisModuleEvent,
+    _subevent: u64,
+    data: *mut c_void,
+) {
+    let si = &*(data as *const raw::RedisModuleSwapDbInfoV1);
+    registry::swap(si.dbnum_first, si.dbnum_second);
+
+    // cached graphs follow their keys to the other db
+    let mut indices = INDICES.write().unwrap();
+    *indices = indices
+        .drain()
+        .map(|((db, name), index)| {
+            let db = match db {
+                db if db == si.dbnum_first => si.dbnum_second,
+                db if db == si.dbnum_second => si.dbnum_first,
+                db => db,
+            };
+            ((db, name), index)
+        })
+        .collect();
+}
+
+unsafe extern "C" fn on_loading_event(
+    _ctx: *mut raw::RedisModuleCtx,
+    _eid: raw::RedisModuleEvent,
+    subevent: u64,
+    _data: *mut c_void,
+) {
+    // the dataset is replaced on rdb/aof loading and replica full sync,
+    // index keys are registered again by loaded events and module cmds.
+    if subevent == raw::REDISMODULE_SUBEVENT_LOADING_RDB_START as u64
+        || subevent == raw::REDISMODULE_SUBEVENT_LOADING_AOF_START as u64
+        || subevent == raw::REDISMODULE_SUBEVENT_LOADING_REPL_START as u64
+    {
+        stats::load_started();
+        registry::flush(-1);
+        INDICES.write().unwrap().clear();
+    } else if subevent == raw::REDISMODULE_SUBEVENT_LOADING_ENDED as u64
+        || subevent == raw::REDISMODULE_SUBEVENT_LOADING_FAILED as u64
+    {
+        stats::load_ended();
+    }
+}
+
+unsafe extern "C" fn on_persistence_event(
+    _ctx: *mut raw::RedisModuleCtx,
+    _eid: raw::RedisModuleEvent,
+    subevent: u64,
+    _data: *mut c_void,
+) {
+    // background saves fire the events in the child, only saves on the main thread are timed here,
+    // e.g. SAVE and the shutdown save, redis reports bgsave durations in INFO persistence.
+    if subevent == raw::REDISMODULE_SUBEVENT_PERSISTENCE_RDB_START as u64
+        || subevent == raw::REDISMODULE_SUBEVENT_PERSISTENCE_AOF_START as u64
+        || subevent == raw::REDISMODULE_SUBEVENT_PERSISTENCE_SYNC_RDB_START as u64
+        || subevent == raw::REDISMODULE_SUBEVENT_PERSISTENCE_SYNC_AOF_START as u64
+    {
+        stats::save_started();
+    } else if subevent == raw::REDISMODULE_SUBEVENT_PERSISTENCE_ENDED as u64
+        || subevent == raw::REDISMODULE_SUBEVENT_PERSISTENCE_FAILED as u64
+    {
+        stats::save_ended();
+    }
+}
+
+// subscribe_server_events
+// FLUSHDB/FLUSHALL, SWAPDB and loading don't fire keyspace events for each key,
+// loading and persistence are timed for INFO.
+pub fn subscribe_server_events(ctx: &Context) -> Result<(), RedisError> {
+    let events: [(u32, raw::RedisModuleEventCallback); 4] = [
+        (raw::REDISMODULE_EVENT_FLUSHDB, Some(on_flush_event)),
+        (raw::REDISMODULE_EVENT_SWAPDB, Some(on_swapdb_event)),
+        (raw::REDISMODULE_EVENT_LOADING, Some(on_loading_event)),
+        (
+            raw::REDISMODULE_EVENT_PERSISTENCE,
+            Some(on_persistence_event),
+        ),
+    ];
+    for (id, callback) in events {
+        let event = raw::RedisModuleEvent {
+            id: id as u64,
+            dataver: 1,
+        };
+        let res =
+            unsafe { raw::RedisModule_SubscribeToServerEvent.unwrap()(ctx.ctx, event, callback) };
+        if res != raw::REDISMODULE_OK as c_int {
+            return Err(RedisError::String(format!(
+                "subscribe server event {} err",
+                id
+            )));
+        }
+    }
+    Ok(())
+}
@@ -4,54 +4,215 @@ mod types;
 #[macro_use]
 extern crate lazy_static;
 
+mod events;
+
 use hnswcore::core::{Index, Node};
+use modcore::acl::check_key_acl;
+use modcore::errors::ErrorCode;
+use modcore::lock::write_lock;
+use modcore::{config, errors, info, pool, registry, stats};
+use rand::{rngs::StdRng, SeedableRng};
+use redis_module::configuration::ConfigurationFlags;
 use redis_module::{
-    redis_module, Context, NextArg, RedisError, RedisResult, RedisString, RedisValue,
+    raw, redis_module, Context, ContextFlags, InfoContext, NextArg, RedisError, RedisResult,
+    RedisString, RedisValue, Status, ThreadSafeContext,
 };
+use std::cell::RefCell;
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
-use std::sync::{Arc, RwLock};
+use std::os::raw::c_int;
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::Instant;
 use types::*;
 
-static PREFIX: &str = "hnsw";
+pub(crate) static PREFIX: &str = "hnsw";
+static SCAN_DEFAULT_COUNT: usize = 10;
+// the redis LAZYFREE_THRESHOLD, values with more allocations are freed in background
+static LAZYFREE_THRESHOLD: usize = 64;
 
 type IndexT = Index<f32, f32>;
 type IndexArc = Arc<RwLock<IndexT>>;
+// cached graph key: (db, index key name), the same index name maybe in different dbs
+type IndexKey = (i32, String);
 lazy_static! {
-    static ref INDICES: RwLock<HashMap<String, IndexArc>> = RwLock::new(HashMap::new());
+    // graphs loaded from redis index and node keys,
+    // invalidated by keyspace and server events which change them outside of module cmds.
+    static ref INDICES: RwLock<HashMap<IndexKey, IndexArc>> = RwLock::new(HashMap::new());
+    // legacy index key args which are logged as deprecated, each is logged once
+    static ref LEGACY_ARGS: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+}
+
+// drop_graph
+// drop the graph in a background thread if it's big, like the redis lazyfree of big values,
+// the last reference of a cached or compact graph maybe dropped on the main thread.
+fn drop_graph(graph: IndexArc) {
+    let node_count = graph.try_read().map(|g| g.node_count).unwrap_or(0);
+    if node_count > LAZYFREE_THRESHOLD {
+        thread::spawn(move || drop(graph));
+    }
+}
+
+fn get_selected_db(ctx: &Context) -> i32 {
+    unsafe { raw::RedisModule_GetSelectedDb.unwrap()(ctx.get_raw()) }
+}
+
+// index_key_name
+// the cmd key arg is the index key name hash tagged by the index name, e.g. hnsw.{idx0},
+// so acl key patterns (e.g. ~hnsw.{tenantA*) and cluster slots apply to the index key,
+// and the index key and its node keys (hnsw.{idx0}.n1) are in the same cluster slot.
+// note: the legacy args are deprecated, they're accepted until the index is migrated by hnsw.index.migrate:
+// the legacy index key hnsw.idx0, and the index name idx0 of the cmds before the hash tag,
+// which is the legacy index key if it exists, hnsw.{idx0} otherwise. the index name isn't the key,
+// so the acl of the index key is checked explicitly, and it isn't routed to the index key in a cluster.
+fn index_key_name(ctx: &Context, name: &str, write: bool) -> Result<String, RedisError> {
+    if index_tag(name).is_some() {
+        return Ok(name.to_string());
+    }
+    let legacy_key = name.strip_prefix(PREFIX).and_then(|n| n.strip_prefix('.'));
+    if name.is_empty() || name.contains(['{', '}']) || legacy_key == Some("") {
+        return Err(errors::bad_param(format!(
+            "index key {} must be {}.{{<name>}}",
+            name, PREFIX
+        )));
+    }
+
+    let key_name = match legacy_key {
+        Some(_) => name.to_string(),
+        None => {
+            let legacy_name = format!("{}.{}", PREFIX, name);
+            let key_name = match ctx
+                .open_key(&ctx.create_string(legacy_name.as_str()))
+                .is_null()
+            {
+                true => format!("{}.{{{}}}", PREFIX, name),
+                false => legacy_name,
+            };
+            check_key_acl(ctx, &ctx.create_string(key_name.as_str()), write)?;
+            key_name
+        }
+    };
+    if LEGACY_ARGS.lock().unwrap().insert(name.to_string()) {
+        let msg = match key_name
+            .strip_prefix(PREFIX)
+            .and_then(|n| n.strip_prefix('.'))
+        {
+            Some(n) if index_tag(&key_name).is_none() => format!(
+                "legacy index key {} is deprecated, migrate it by {}.index.migrate {} {}.{{{}}}",
+                key_name, PREFIX, key_name, PREFIX, n
+            ),
+            _ => format!("index key {} is deprecated, use {}", name, key_name),
+        };
+        ctx.log_warning(msg.as_str());
+    }
+    Ok(key_name)
+}
+
+// index name in the hash tag of the index key name, e.g. idx0 of hnsw.{idx0}
+fn index_tag(name: &str) -> Option<&str> {
+    name.strip_prefix(PREFIX)
+        .and_then(|n| n.strip_prefix(".{"))
+        .and_then(|n| n.strip_suffix('}'))
+        .filter(|n| !n.is_empty() && !n.contains('}'))
+}
+
+// node_seed
+// node level is drawn from the rng seeded by the node name (FNV-1a),
+// so replicas and aof replay build the same graph as the master.
+fn node_seed(node_name: &str) -> u64 {
+    node_name.bytes().fold(0xcbf29ce484222325, |h, b| {
+        (h ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+// parse_vector
+// the vector args are floats, malformed floats are BADPARAM errors instead of panics
+fn parse_vector(args: impl Iterator<Item = RedisString>) -> Result<Vec<f32>, RedisError> {
+    args.map(|d| {
+        d.parse_float()
+            .map(|f| f as f32)
+            .map_err(|_| errors::bad_param(format!("vector item {} is not a float", d)))
+    })
+    .collect()
+}
+
+fn check_dimensions(index: &IndexT, data: &[f32]) -> Result<(), RedisError> {
+    if data.len() != index.data_dim {
+        return Err(errors::error(
+            ErrorCode::DimMismatch,
+            format!(
+                "vector dimensions {} does not match Index: {} dimensions {}",
+                data.len(),
+                index.name,
+                index.data_dim
+            ),
+        ));
+    }
+    Ok(())
 }
 
 // create_index
-// cmd: hnsw.index.create indexName [algo_param_key algo_param_value]
-// cmd eg: hnsw.index.create idx0 dim 3 m 10 efcon 12
+// cmd: hnsw.index.create indexName [algo_param_key algo_param_value] [storage keys|compact]
+// cmd eg: hnsw.index.create hnsw.{idx0} dim 3 m 10 efcon 12
+// cmd eg: hnsw.index.create hnsw.{idx0} dim 3 m 10 efcon 12 storage compact
 // return "OK" or error
+// note: storage keys (default) saves each node as a node key,
+// storage compact keeps the whole graph in the index value without node keys,
+// it's saved in the rdb with integer node ids and contiguous vectors.
 fn create_index(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     ctx.auto_memory();
 
-    if args.len() < 8 {
+    if args.len() != 8 && args.len() != 10 {
         return Err(RedisError::WrongArity);
     }
 
     let mut args = args.into_iter().skip(1);
 
-    let name = format!("{}.{}", PREFIX, args.next_str()?);
+    let name = index_key_name(ctx, args.next_str()?, true)?;
+    // legacy index keys are only accepted until they're migrated, new index keys are hash tagged
+    if index_tag(&name).is_none() {
+        return Err(errors::bad_param(format!(
+            "index key {} must be {}.{{<name>}}",
+            name, PREFIX
+        )));
+    }
     let index_name = ctx.create_string(name.clone());
 
-    if args.next_str()?.to_lowercase() != "dim" {
-        return Err(RedisError::WrongArity);
+    let param = args.next_str()?;
+    if param.to_lowercase() != "dim" {
+        return Err(errors::bad_param(format!("need dim param but {}", param)));
     }
     let dim = args.next_u64()? as usize;
 
-    if args.next_str()?.to_lowercase() != "m" {
-        return Err(RedisError::WrongArity);
+    let param = args.next_str()?;
+    if param.to_lowercase() != "m" {
+        return Err(errors::bad_param(format!("need m param but {}", param)));
     }
     let m = args.next_u64()? as usize;
 
-    if args.next_str()?.to_lowercase() != "efcon" {
-        return Err(RedisError::WrongArity);
+    let param = args.next_str()?;
+    if param.to_lowercase() != "efcon" {
+        return Err(errors::bad_param(format!("need efcon param but {}", param)));
     }
     let ef_construction = args.next_u64()? as usize;
 
+    let mut compact = false;
+    if let Ok(arg) = args.next_str() {
+        if arg.to_lowercase() != "storage" {
+            return Err(errors::bad_param(format!("need storage param but {}", arg)));
+        }
+        compact = match args.next_str()?.to_lowercase().as_str() {
+            "keys" => false,
+            "compact" => true,
+            storage => {
+                return Err(errors::bad_param(format!(
+                    "unsupported storage: {}",
+                    storage
+                )))
+            }
+        };
+    }
+
     // write to redis
     let key = ctx.open_key_writable(&index_name);
     match key.get_value::<IndexRedis>(&HNSW_INDEX_REDIS_TYPE)? {
@@ -71,15 +232,24 @@ fn create_index(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
                 ef_construction,
             );
             ctx.log_debug(format!("{:?}", index).as_str());
-            key.set_value::<IndexRedis>(&HNSW_INDEX_REDIS_TYPE, index.clone().into())?;
-            // Add index to global hashmap
-            INDICES
-                .write()
-                .unwrap()
-                .insert(name, Arc::new(RwLock::new(index)));
+            let db = get_selected_db(ctx);
+            if compact {
+                key.set_value::<IndexRedis>(&HNSW_INDEX_REDIS_TYPE, IndexRedis::compact(index))?;
+                registry::add(db, &name);
+            } else {
+                key.set_value::<IndexRedis>(&HNSW_INDEX_REDIS_TYPE, index.clone().into())?;
+                registry::add(db, &name);
+                // Add index to global hashmap
+                INDICES
+                    .write()
+                    .unwrap()
+                    .insert((db, name.clone()), Arc::new(RwLock::new(index)));
+            }
         }
     }
 
+    events::notify_keyspace_event(ctx, &format!("{}.index.create", PREFIX), &name);
+    ctx.replicate_verbatim();
     Ok("OK".into())
 }
 
@@ -92,7 +262,7 @@ fn make_index<'a>(ctx: &'a Context, ir: &IndexRedis) -> Result<IndexT, RedisErro
 
         let nr = key
             .get_value::<NodeRedis>(&HNSW_NODE_REDIS_TYPE)?
-            .ok_or_else(|| RedisError::String(format!("Node: {} does not exist", node_name)))?;
+            .ok_or_else(|| errors::no_node(&node_name))?;
 
         let node = Node::new(node_name, &nr.data, index.m_max_0);
         index.nodes.insert(node_name.to_owned(), node);
@@ -106,13 +276,14 @@ fn make_index<'a>(ctx: &'a Context, ir: &IndexRedis) -> Result<IndexT, RedisErro
 
         let nr = key
             .get_value::<NodeRedis>(&HNSW_NODE_REDIS_TYPE)?
-            .ok_or_else(|| RedisError::String(format!("Node: {} does not exist", node_name)))?;
+            .ok_or_else(|| errors::no_node(&node_name))?;
         for layer in &nr.neighbors {
             let mut node_layer = Vec::with_capacity(layer.len());
             for neighbor in layer {
-                let nn = index.nodes.get(neighbor).ok_or_else(|| {
-                    RedisError::String(format!("Node: {} does not exist", neighbor))
-                })?;
+                let nn = index
+                    .nodes
+                    .get(neighbor)
+                    .ok_or_else(|| errors::no_node(&neighbor))?;
                 node_layer.push(nn.downgrade());
             }
             target.write().neighbors.push(node_layer);
@@ -126,7 +297,7 @@ fn make_index<'a>(ctx: &'a Context, ir: &IndexRedis) -> Result<IndexT, RedisErro
             let node = index
                 .nodes
                 .get(node_name)
-                .ok_or_else(|| RedisError::String(format!("Node: {} does not exist", node_name)))?;
+                .ok_or_else(|| errors::no_node(&node_name))?;
             node_layer.insert(node.downgrade());
         }
         index.layers.push(node_layer);
@@ -138,7 +309,7 @@ fn make_index<'a>(ctx: &'a Context, ir: &IndexRedis) -> Result<IndexT, RedisErro
             let node = index
                 .nodes
                 .get(node_name)
-                .ok_or_else(|| RedisError::String(format!("Node: {} does not exist", node_name)))?;
+                .ok_or_else(|| errors::no_node(&node_name))?;
             Some(node.downgrade())
         }
         None => None,
@@ -150,7 +321,7 @@ fn make_index<'a>(ctx: &'a Context, ir: &IndexRedis) -> Result<IndexT, RedisErro
 fn load_index<'a>(ctx: &'a Context, index_name: &str) -> Result<IndexArc, RedisError> {
     let mut indices = INDICES.write().unwrap();
     // check if index is in global hashmap
-    let index = match indices.entry(index_name.to_string()) {
+    let index = match indices.entry((get_selected_db(ctx), index_name.to_string())) {
         Entry::Occupied(o) => o.into_mut(),
         // if index isn't present, load it from redis
         Entry::Vacant(v) => {
@@ -160,9 +331,11 @@ fn load_index<'a>(ctx: &'a Context, index_name: &str) -> Result<IndexArc, RedisE
 
             let index_redis = rkey
                 .get_value::<IndexRedis>(&HNSW_INDEX_REDIS_TYPE)?
-                .ok_or_else(|| {
-                    RedisError::String(format!("Index: {} does not exist", index_name))
-                })?;
+                .ok_or_else(|| errors::no_index(&index_name))?;
+            // compact storage graph lives in the index value, don't cache it
+            if let Some(graph) = &index_redis.graph {
+                return Ok(graph.clone());
+            }
 
             let index = make_index(ctx, index_redis)?;
             v.insert(Arc::new(RwLock::new(index)))
@@ -172,9 +345,19 @@ fn load_index<'a>(ctx: &'a Context, index_name: &str) -> Result<IndexArc, RedisE
     Ok(index.clone())
 }
 
+// load_compact_graph
+// the graph of compact storage index, None if the index is stored in node keys or doesn't exist
+fn load_compact_graph(ctx: &Context, index_name: &str) -> Option<IndexArc> {
+    let rkey = ctx.open_key(&ctx.create_string(index_name.to_string()));
+    match rkey.get_value::<IndexRedis>(&HNSW_INDEX_REDIS_TYPE) {
+        Ok(Some(index_redis)) => index_redis.graph.clone(),
+        _ => None,
+    }
+}
+
 // get_index
 // cmd: hnsw.index.get indexName
-// cmd eg: hnsw.index.get idx0
+// cmd eg: hnsw.index.get hnsw.{idx0}
 // return indexInfo or error
 fn get_index(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     ctx.auto_memory();
@@ -184,7 +367,7 @@ fn get_index(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     }
 
     let mut args = args.into_iter().skip(1);
-    let index_name = format!("{}.{}", PREFIX, args.next_str()?);
+    let index_name = index_key_name(ctx, args.next_str()?, false)?;
 
     let index = load_index(ctx, index_name.as_str())?;
     let index = index
@@ -195,9 +378,13 @@ fn get_index(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     ctx.log_debug(format!("Layers: {:?}", index.layers.len()).as_str());
     ctx.log_debug(format!("Nodes: {:?}", index.nodes.len()).as_str());
 
-    let index_redis: IndexRedis = index.clone().into();
+    let mut index_redis: IndexRedis = index.clone().into();
+    index_redis.graph = load_compact_graph(ctx, &index_name);
+    let mem_usage = index_redis.mem_usage(get_selected_db(ctx), 0);
 
-    Ok(index_redis.into())
+    let mut reply: Vec<RedisValue> = index_redis.into();
+    reply.extend(Vec::<RedisValue>::from(mem_usage));
+    Ok(reply.into())
 }
 
 fn delete_node_redis<'a>(ctx: &'a Context, node_name: &str) -> Result<(), RedisError> {
@@ -206,10 +393,7 @@ fn delete_node_redis<'a>(ctx: &'a Context, node_name: &str) -> Result<(), RedisE
     match rkey.get_value::<NodeRedis>(&HNSW_NODE_REDIS_TYPE)? {
         Some(_) => rkey.delete()?,
         None => {
-            return Err(RedisError::String(format!(
-                "Node: {} does not exist",
-                node_name
-            )));
+            return Err(errors::no_node(node_name));
         }
     };
 
@@ -218,7 +402,7 @@ fn delete_node_redis<'a>(ctx: &'a Context, node_name: &str) -> Result<(), RedisE
 
 // delete_index
 // cmd: hnsw.index.del indexName
-// cmd eg: hnsw.index.del idx0
+// cmd eg: hnsw.index.del hnsw.{idx0}
 // return 1 or error
 fn delete_index(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     ctx.auto_memory();
@@ -228,40 +412,194 @@ fn delete_index(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     }
 
     let mut args = args.into_iter().skip(1);
-    let index_name = format!("{}.{}", PREFIX, args.next_str()?);
+    let index_name = index_key_name(ctx, args.next_str()?, true)?;
 
     // get index from global hashmap
-    load_index(ctx, index_name.as_str())?;
-    let mut indices = INDICES.write().unwrap();
-    let index = indices
-        .remove(&index_name)
-        .ok_or_else(|| RedisError::String(format!("Index: {} does not exist", index_name)))?;
-    let index = index
-        .try_read()
-        .map_err(|e| RedisError::String(e.to_string()))?;
-
-    // delete index nodes from redis
-    for (node_name, _) in index.nodes.iter() {
-        delete_node_redis(ctx, node_name.as_str())?;
+    let graph = load_index(ctx, index_name.as_str())?;
+    INDICES
+        .write()
+        .unwrap()
+        .remove(&(get_selected_db(ctx), index_name.clone()));
+
+    // delete index nodes from redis, compact storage nodes are freed with the index
+    if load_compact_graph(ctx, &index_name).is_none() {
+        let index = graph
+            .try_read()
+            .map_err(|e| RedisError::String(e.to_string()))?;
+        for (node_name, _) in index.nodes.iter() {
+            delete_node_redis(ctx, node_name.as_str())?;
+        }
     }
 
-    // get index from redis
+    // get index from redis, a big index is freed in the redis lazyfree thread
     ctx.log_debug(format!("deleting index: {}", index_name).as_str());
     let rkey = ctx.open_key_writable(&ctx.create_string(index_name.clone()));
 
     match rkey.get_value::<IndexRedis>(&HNSW_INDEX_REDIS_TYPE)? {
-        Some(_) => rkey.delete()?,
+        Some(_) => rkey.unlink()?,
         None => {
-            return Err(RedisError::String(format!(
-                "Index: {} does not exist",
-                index_name
-            )));
+            return Err(errors::no_index(index_name));
         }
     };
+    registry::remove(get_selected_db(ctx), &index_name);
+    drop_graph(graph);
 
+    events::notify_keyspace_event(ctx, &format!("{}.index.del", PREFIX), &index_name);
+    ctx.replicate_verbatim();
     Ok(1_usize.into())
 }
 
+// migrate_index
+// cmd: hnsw.index.migrate legacyIndexName indexName
+// cmd eg: hnsw.index.migrate hnsw.idx0 hnsw.{idx0}
+// return count of migrated node keys or error
+// note: move the legacy index key hnsw.idx0 and node keys hnsw.idx0.* to hnsw.{idx0} and hnsw.{idx0}.*,
+// legacy keys of one index maybe in different cluster slots, so migrate before sharding.
+fn migrate_index(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    ctx.auto_memory();
+
+    if args.len() != 3 {
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+    let legacy_name = args.next_string()?;
+    let index_name = args.next_string()?;
+    if index_tag(&index_name).is_none() {
+        return Err(errors::bad_param(format!(
+            "index key {} must be {}.{{<name>}}",
+            index_name, PREFIX
+        )));
+    }
+    if index_tag(&index_name).map(|name| format!("{}.{}", PREFIX, name))
+        != Some(legacy_name.clone())
+    {
+        return Err(errors::bad_param(format!(
+            "legacy index key {} must be the untagged {}",
+            legacy_name, index_name
+        )));
+    }
+
+    let key = ctx.open_key_writable(&ctx.create_string(index_name.clone()));
+    if !key.is_empty() {
+        return Err(RedisError::String(format!(
+            "Index: {} already exists",
+            index_name
+        )));
+    }
+    let legacy_key = ctx.open_key_writable(&ctx.create_string(legacy_name.clone()));
+    let legacy_index = legacy_key
+        .get_value::<IndexRedis>(&HNSW_INDEX_REDIS_TYPE)?
+        .ok_or_else(|| errors::no_index(&legacy_name))?;
+
+    // check all node keys before moving, so the migration doesn't stop halfway
+    let mut nodes = Vec::with_capacity(legacy_index.nodes.len());
+    for node_name in &legacy_index.nodes {
+        let nkey = ctx.open_key(&ctx.create_string(node_name.clone()));
+        let node = nkey
+            .get_value::<NodeRedis>(&HNSW_NODE_REDIS_TYPE)?
+            .ok_or_else(|| errors::no_node(&node_name))?;
+        nodes.push((node_name.clone(), node.renamed(&legacy_name, &index_name)));
+    }
+
+    let count = nodes.len();
+    for (node_name, node) in nodes {
+        ctx.log_debug(format!("migrate node: {}", node_name).as_str());
+        ctx.open_key_writable(&ctx.create_string(node_name.clone()))
+            .delete()?;
+        let new_node_name = rename_node(&node_name, &legacy_name, &index_name);
+        ctx.open_key_writable(&ctx.create_string(new_node_name))
+            .set_value(&HNSW_NODE_REDIS_TYPE, node)?;
+    }
+
+    let index = legacy_index.renamed(&index_name);
+    legacy_key.delete()?;
+    key.set_value(&HNSW_INDEX_REDIS_TYPE, index)?;
+
+    let db = get_selected_db(ctx);
+    registry::remove(db, &legacy_name);
+    registry::add(db, &index_name);
+    INDICES.write().unwrap().remove(&(db, legacy_name));
+
+    events::notify_keyspace_event(ctx, &format!("{}.index.migrate", PREFIX), &index_name);
+    ctx.replicate_verbatim();
+    Ok(count.into())
+}
+
+// rename_key
+// cmd: hnsw.key.rename keyName fromKeyName
+// return "OK" or error
+// note: the rename_to event job of RENAME renames the index or node value on the master,
+// and replicates the rename to replicas and the aof by it, clients can't call it.
+fn rename_key(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    ctx.auto_memory();
+    if !ctx
+        .get_flags()
+        .intersects(ContextFlags::REPLICATED | ContextFlags::LOADING)
+    {
+        return Err(RedisError::String(format!(
+            "ERR {}.key.rename is only replicated by RENAME",
+            PREFIX
+        )));
+    }
+    if args.len() != 3 {
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+    let key_name = args.next_str()?;
+    let from_name = args.next_str()?;
+    events::rename_value(ctx, key_name, from_name)?;
+    ctx.replicate_verbatim();
+    Ok("OK".into())
+}
+
+// list_index
+// cmd: hnsw.index.list
+// cmd eg: hnsw.index.list
+// return index summary infos in the current db
+fn list_index(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    ctx.auto_memory();
+
+    if args.len() != 1 {
+        return Err(RedisError::WrongArity);
+    }
+
+    let db = get_selected_db(ctx);
+    let mut reply: Vec<RedisValue> = Vec::new();
+    for index_name in registry::list(db) {
+        let key_name = ctx.create_string(index_name.clone());
+        // only list the indexes which the user can read
+        if check_key_acl(ctx, &key_name, false).is_err() {
+            continue;
+        }
+        let key = ctx.open_key(&key_name);
+        let index_redis = match key.get_value::<IndexRedis>(&HNSW_INDEX_REDIS_TYPE) {
+            Ok(Some(index_redis)) => index_redis,
+            _ => {
+                ctx.log_warning(format!("registry Index: {} does not exist", index_name).as_str());
+                registry::remove(db, &index_name);
+                continue;
+            }
+        };
+
+        let mut info: Vec<RedisValue> = Vec::new();
+        info.push("name".into());
+        info.push(index_name.into());
+        info.push("node_count".into());
+        info.push(index_redis.node_count.into());
+        info.push("data_dim".into());
+        info.push(index_redis.data_dim.into());
+        info.push("metric".into());
+        info.push(index_redis.mfunc_kind.as_str().into());
+        info.push("mem_usage".into());
+        info.push(index_redis.mem_usage(db, 0).total().into());
+        reply.push(info.into());
+    }
+
+    Ok(reply.into())
+}
+
 fn write_node<'a>(ctx: &'a Context, key: &str, node: NodeRedis) -> Result<(), RedisError> {
     ctx.log_debug(format!("set key: {}", key).as_str());
     let rkey = ctx.open_key_writable(&ctx.create_string(key));
@@ -281,15 +619,18 @@ fn write_node<'a>(ctx: &'a Context, key: &str, node: NodeRedis) -> Result<(), Re
 fn update_index<'a>(ctx: &'a Context, index_name: &str, index: &IndexT) -> Result<(), RedisError> {
     let key = ctx.open_key_writable(&ctx.create_string(index_name));
     match key.get_value::<IndexRedis>(&HNSW_INDEX_REDIS_TYPE)? {
-        Some(_) => {
+        // compact storage graph is updated in place
+        Some(index_redis) if index_redis.is_compact() => {
+            index_redis.node_count = index.node_count;
+            index_redis.max_layer = index.max_layer;
+        }
+        // updated in place, set_value would unlink the index value with its cached graph
+        Some(index_redis) => {
             ctx.log_debug(format!("update index: {}", index_name).as_str());
-            key.set_value::<IndexRedis>(&HNSW_INDEX_REDIS_TYPE, index.clone().into())?;
+            *index_redis = index.clone().into();
         }
         None => {
-            return Err(RedisError::String(format!(
-                "Index: {} does not exist",
-                index_name
-            )));
+            return Err(errors::no_index(index_name));
         }
     }
     Ok(())
@@ -297,7 +638,7 @@ fn update_index<'a>(ctx: &'a Context, index_name: &str, index: &IndexT) -> Resul
 
 // add_node
 // cmd: hnsw.node.add indexName nodeName dataVector
-// cmd eg: hnsw.node.add idx0 n1 0.6 0.1 0.1
+// cmd eg: hnsw.node.add hnsw.{idx0} n1 0.6 0.1 0.1
 // return "OK" or error
 // todo: batch add
 fn add_node(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
@@ -306,44 +647,57 @@ fn add_node(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     if args.len() <= 3 {
         return Err(RedisError::WrongArity);
     }
+    let _timer = stats::Timer::new(stats::Op::Insert);
 
     let mut args = args.into_iter().skip(1);
-    let index_name = format!("{}.{}", PREFIX, args.next_str()?);
+    let index_name = index_key_name(ctx, args.next_str()?, true)?;
     let node_name = format!("{}.{}", index_name, args.next_str()?);
 
-    let data = args
-        .into_iter()
-        .map(|d| d.parse_float().unwrap() as f32)
-        .collect::<Vec<f32>>();
+    let data = parse_vector(args)?;
 
     // load index from redisIndex
     let index = load_index(ctx, index_name.as_str())?;
-    let mut index = index
-        .try_write()
-        .map_err(|e| RedisError::String(e.to_string()))?;
+    // wait for the searches on workers which hold the read lock, TRYAGAIN if they don't finish in time
+    let mut index = write_lock(ctx, &index_name, &index)?;
+    let compact = load_compact_graph(ctx, &index_name).is_some();
 
     // add node to index
     ctx.log_debug(format!("Adding node: {} to Index: {}", &node_name, &index_name).as_str());
+    let write_err = RefCell::new(None);
     let up = |name: String, node: Node<f32>| {
-        write_node(ctx, &name, (&node).into()).unwrap();
+        if !compact {
+            if let Err(e) = write_node(ctx, &name, (&node).into()) {
+                write_err.borrow_mut().get_or_insert(e);
+            }
+        }
     };
+    check_dimensions(&index, &data)?;
+    index.rng_ = StdRng::seed_from_u64(node_seed(&node_name));
     index
         .add_node(node_name.as_str(), &data, up)
         .map_err(|e| RedisError::String(e.error_string()))?;
 
     // write node to redis
-    let node = index.nodes.get(&node_name).unwrap();
-    write_node(ctx, node_name.as_str(), node.into())?;
+    if !compact {
+        let node = index.nodes.get(&node_name).unwrap();
+        write_node(ctx, node_name.as_str(), node.into())?;
+    }
 
     // update index in redis
     update_index(ctx, &index_name, &index)?;
 
+    events::notify_keyspace_event(ctx, &format!("{}.node.add", PREFIX), &index_name);
+    ctx.replicate_verbatim();
+    // the graph is changed in memory, so the cmd is replicated before the node write error is returned
+    if let Some(e) = write_err.into_inner() {
+        return Err(e);
+    }
     Ok("OK".into())
 }
 
 // get_node
 // cmd: hnsw.node.get indexName nodeName
-// cmd eg: hnsw.node.get idx0 n1
+// cmd eg: hnsw.node.get hnsw.{idx0} n1
 // return nodeInfo or error
 // todo: batch get
 fn get_node(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
@@ -354,21 +708,34 @@ fn get_node(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     }
 
     let mut args = args.into_iter().skip(1);
-    let index_name = format!("{}.{}", PREFIX, args.next_str()?);
+    let index_name = index_key_name(ctx, args.next_str()?, false)?;
     let node_name = format!("{}.{}", index_name, args.next_str()?);
 
+    // get node from compact storage graph
+    if let Some(graph) = load_compact_graph(ctx, &index_name) {
+        let graph = graph
+            .try_read()
+            .map_err(|e| RedisError::String(e.to_string()))?;
+        let node = graph
+            .nodes
+            .get(&node_name)
+            .ok_or_else(|| errors::no_node(&node_name))?;
+        let value: NodeRedis = node.into();
+        return Ok((&value).into());
+    }
+
     // get node from redis
     let key = ctx.open_key(&ctx.create_string(node_name.clone()));
     let value = key
         .get_value::<NodeRedis>(&HNSW_NODE_REDIS_TYPE)?
-        .ok_or_else(|| RedisError::String(format!("Node: {} does not exist", &node_name)))?;
+        .ok_or_else(|| errors::no_node(&node_name))?;
 
     Ok(value.into())
 }
 
 // delete_node
 // cmd: hnsw.node.del indexName nodeName
-// cmd eg: hnsw.node.del idx0 n1
+// cmd eg: hnsw.node.del hnsw.{idx0} n1
 // return 1 or error
 // todo: batch del
 fn delete_node(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
@@ -377,19 +744,22 @@ fn delete_node(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     if args.len() != 3 {
         return Err(RedisError::WrongArity);
     }
+    let _timer = stats::Timer::new(stats::Op::Delete);
 
     let mut args = args.into_iter().skip(1);
-    let index_name = format!("{}.{}", PREFIX, args.next_str()?);
+    let index_name = index_key_name(ctx, args.next_str()?, true)?;
     let node_name = format!("{}.{}", index_name, args.next_str()?);
 
     // load index from redisIndex
     let index = load_index(ctx, index_name.as_str())?;
-    let mut index = index
-        .try_write()
-        .map_err(|e| RedisError::String(e.to_string()))?;
+    // wait for the searches on workers which hold the read lock, TRYAGAIN if they don't finish in time
+    let mut index = write_lock(ctx, &index_name, &index)?;
 
     // get node from redisNode with Arc(atomic ref count)
-    let node = index.nodes.get(&node_name).unwrap();
+    let node = index
+        .nodes
+        .get(&node_name)
+        .ok_or_else(|| errors::no_node(&node_name))?;
     if Arc::strong_count(&node.0) > 1 {
         return Err(RedisError::String(format!(
             "{} is being accessed, unable to delete. Try again later",
@@ -398,26 +768,166 @@ fn delete_node(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     }
 
     // delete node from index
+    let compact = load_compact_graph(ctx, &index_name).is_some();
+    let write_err = RefCell::new(None);
     let up = |name: String, node: Node<f32>| {
-        write_node(ctx, &name, (&node).into()).unwrap();
+        if !compact {
+            if let Err(e) = write_node(ctx, &name, (&node).into()) {
+                write_err.borrow_mut().get_or_insert(e);
+            }
+        }
     };
     index
         .delete_node(&node_name, up)
         .map_err(|e| RedisError::String(e.error_string()))?;
 
     // delete node from redisIndex
-    delete_node_redis(ctx, &node_name)?;
+    if !compact {
+        delete_node_redis(ctx, &node_name)?;
+    }
 
     // update index in redis
     update_index(ctx, &index_name, &index)?;
 
+    events::notify_keyspace_event(ctx, &format!("{}.node.del", PREFIX), &index_name);
+    ctx.replicate_verbatim();
+    // the graph is changed in memory, so the cmd is replicated before the node write error is returned
+    if let Some(e) = write_err.into_inner() {
+        return Err(e);
+    }
     Ok(1_usize.into())
 }
 
+// scan_node
+// cmd: hnsw.node.scan indexName cursor [COUNT count] [WITHVECTORS]
+// cmd eg: hnsw.node.scan hnsw.{idx0} 0 COUNT 10 WITHVECTORS
+// return [nextCursor, [nodeInfo...]] or error, nextCursor 0 means the scan is complete.
+// note: the cursor is the node name to continue from, nodes are scanned in name order,
+// so the nodes which exist from the start to the end of a full iteration are returned exactly once.
+fn scan_node(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    ctx.auto_memory();
+
+    if args.len() < 3 || args.len() > 6 {
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+    let index_name = index_key_name(ctx, args.next_str()?, false)?;
+    let cursor = args.next_string()?;
+
+    let mut count = SCAN_DEFAULT_COUNT;
+    let mut with_vectors = false;
+    while let Ok(arg) = args.next_string() {
+        match arg.to_lowercase().as_str() {
+            "count" => {
+                count = args.next_u64()? as usize;
+                if count == 0 {
+                    return Err(errors::bad_param("COUNT must be greater than 0"));
+                }
+            }
+            "withvectors" => with_vectors = true,
+            _ => return Err(errors::bad_param(format!("unknown param {}", arg))),
+        }
+    }
+
+    // scan node names from redis index, don't need to load the whole graph
+    let key = ctx.open_key(&ctx.create_string(index_name.clone()));
+    let index_redis = key
+        .get_value::<IndexRedis>(&HNSW_INDEX_REDIS_TYPE)?
+        .ok_or_else(|| errors::no_index(&index_name))?;
+
+    // page of node names from the cursor, with the next cursor
+    let (page, next_cursor) = match &index_redis.graph {
+        None => {
+            let start = match cursor.as_str() {
+                "0" => 0,
+                _ => index_redis
+                    .nodes
+                    .partition_point(|n| n.as_str() < cursor.as_str()),
+            };
+            let end = std::cmp::min(start + count, index_redis.nodes.len());
+            (
+                index_redis.nodes[start..end].to_vec(),
+                index_redis.nodes.get(end).cloned(),
+            )
+        }
+        // compact storage graph nodes aren't sorted, select the smallest count+1 names from the cursor
+        Some(graph) => {
+            let graph = graph
+                .try_read()
+                .map_err(|e| RedisError::String(e.to_string()))?;
+            let mut names = graph
+                .nodes
+                .keys()
+                .filter(|n| cursor == "0" || n.as_str() >= cursor.as_str())
+                .collect::<Vec<&String>>();
+            if names.len() > count {
+                names.select_nth_unstable(count);
+                names.truncate(count + 1);
+            }
+            names.sort_unstable();
+            let next_cursor = names.get(count).map(|n| n.to_string());
+            names.truncate(count);
+            (
+                names.into_iter().cloned().collect::<Vec<String>>(),
+                next_cursor,
+            )
+        }
+    };
+    let next_cursor = next_cursor.unwrap_or_else(|| "0".to_string());
+
+    let mut nodes: Vec<RedisValue> = Vec::with_capacity(page.len());
+    for node_name in page {
+        let mut node: Vec<RedisValue> = Vec::new();
+        node.push("name".into());
+        node.push(node_name.as_str().into());
+        if with_vectors {
+            let data = match &index_redis.graph {
+                Some(graph) => {
+                    let graph = graph
+                        .try_read()
+                        .map_err(|e| RedisError::String(e.to_string()))?;
+                    match graph.nodes.get(&node_name) {
+                        Some(n) => n.read().data.clone(),
+                        None => return Err(errors::no_node(node_name)),
+                    }
+                }
+                None => {
+                    let nkey = ctx.open_key(&ctx.create_string(node_name.clone()));
+                    let nr = nkey
+                        .get_value::<NodeRedis>(&HNSW_NODE_REDIS_TYPE)?
+                        .ok_or_else(|| errors::no_node(&node_name))?;
+                    nr.data.clone()
+                }
+            };
+            node.push("data".into());
+            node.push(data.iter().map(|x| *x as f64).collect::<Vec<f64>>().into());
+        }
+        nodes.push(node.into());
+    }
+
+    ctx.log_debug(
+        format!(
+            "Scan Index: {} cursor {} count {} next cursor {}",
+            index_name,
+            cursor,
+            nodes.len(),
+            next_cursor
+        )
+        .as_str(),
+    );
+
+    let mut reply: Vec<RedisValue> = Vec::new();
+    reply.push(next_cursor.into());
+    reply.push(nodes.into());
+
+    Ok(reply.into())
+}
+
 // search_kann
 // k-Approximate Nearest Neighbors (kANN) Search
 // cmd: hnsw.search.kann indexName topK queryVector
-// cmd eg: hnsw.search.kann idx0 6 0.0 0.0 0.0
+// cmd eg: hnsw.search.kann hnsw.{idx0} 6 0.0 0.0 0.0
 // return top K ANN node infos or error
 // todo: add filter
 fn search_kann(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
@@ -426,24 +936,41 @@ fn search_kann(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     if args.len() <= 3 {
         return Err(RedisError::WrongArity);
     }
+    let timer = stats::Timer::new(stats::Op::Search);
 
     let mut args = args.into_iter().skip(1);
-    let index_name = format!("{}.{}", PREFIX, args.next_str()?);
+    let index_name = index_key_name(ctx, args.next_str()?, false)?;
     let k = args.next_u64()? as usize;
-    let data = args
-        .into_iter()
-        .map(|d| d.parse_float().unwrap() as f32)
-        .collect::<Vec<f32>>();
+    let data = parse_vector(args)?;
 
     // load index from redis
     let index = load_index(ctx, index_name.as_str())?;
+
+    ctx.log_debug(format!("Searching for {} nearest nodes in Index: {}", k, index_name).as_str());
+
+    // search on a worker with the index read lock, the main thread only loads the index
+    if pool::can_dispatch(ctx) {
+        pool::dispatch(ctx, move |blocked_client| {
+            let _timer = timer;
+            let thread_ctx = ThreadSafeContext::with_blocked_client(blocked_client);
+            let reply = match index.read() {
+                Ok(index) => search_reply(&index, &data, k),
+                Err(e) => Err(RedisError::String(e.to_string())),
+            };
+            thread_ctx.reply(reply);
+        })?;
+        return Ok(RedisValue::NoReply);
+    }
+
     let index = index
         .try_read()
         .map_err(|e| RedisError::String(e.to_string()))?;
+    search_reply(&index, &data, k)
+}
 
-    ctx.log_debug(format!("Searching for {} nearest nodes in Index: {}", k, index_name).as_str());
-
-    match index.search_kann(&data, k) {
+fn search_reply(index: &IndexT, data: &[f32], k: usize) -> RedisResult {
+    check_dimensions(index, data)?;
+    match index.search_kann(data, k) {
         Ok(res) => {
             let mut reply: Vec<RedisValue> = Vec::new();
             reply.push(res.len().into());
@@ -457,6 +984,104 @@ fn search_kann(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     }
 }
 
+// search_profile
+// kANN search with its cost, to tune m and ef_construction
+// cmd: hnsw.search.profile indexName topK queryVector
+// cmd eg: hnsw.search.profile hnsw.{idx0} 6 0.0 0.0 0.0
+// return ["results", [top K ANN node infos], "profile", [usec, visited, distances, layers]] or error
+// note: layers is the greedy path from the top layer to layer 0,
+// the nearest node of a layer is the enterpoint of the next one.
+fn search_profile(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    ctx.auto_memory();
+
+    if args.len() <= 3 {
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+    let index_name = index_key_name(ctx, args.next_str()?, false)?;
+    let k = args.next_u64()? as usize;
+    let data = parse_vector(args)?;
+
+    let index = load_index(ctx, index_name.as_str())?;
+
+    if pool::can_dispatch(ctx) {
+        pool::dispatch(ctx, move |blocked_client| {
+            let thread_ctx = ThreadSafeContext::with_blocked_client(blocked_client);
+            let reply = match index.read() {
+                Ok(index) => search_profile_reply(&index, &data, k),
+                Err(e) => Err(RedisError::String(e.to_string())),
+            };
+            thread_ctx.reply(reply);
+        })?;
+        return Ok(RedisValue::NoReply);
+    }
+
+    let index = index
+        .try_read()
+        .map_err(|e| RedisError::String(e.to_string()))?;
+    search_profile_reply(&index, &data, k)
+}
+
+fn search_profile_reply(index: &IndexT, data: &[f32], k: usize) -> RedisResult {
+    check_dimensions(index, data)?;
+    let start = Instant::now();
+    let (res, stats) = index
+        .search_kann_profile(data, k)
+        .map_err(|e| RedisError::String(e.error_string()))?;
+    let usec = start.elapsed().as_micros() as i64;
+
+    let mut results: Vec<RedisValue> = Vec::new();
+    results.push(res.len().into());
+    for r in &res {
+        let sr: SearchResultRedis = r.into();
+        results.push(sr.into());
+    }
+
+    let layers = stats
+        .layers
+        .into_iter()
+        .map(|l| {
+            let layer: Vec<RedisValue> = vec![
+                "layer".into(),
+                l.layer.into(),
+                "enterpoint".into(),
+                l.enterpoint.into(),
+                "nearest".into(),
+                l.nearest.into(),
+                "ef".into(),
+                l.ef.into(),
+                "visited".into(),
+                l.visited.into(),
+                "distances".into(),
+                l.distances.into(),
+                "usec".into(),
+                (l.usec as i64).into(),
+            ];
+            layer.into()
+        })
+        .collect::<Vec<RedisValue>>();
+
+    let profile: Vec<RedisValue> = vec![
+        "usec".into(),
+        usec.into(),
+        "visited".into(),
+        stats.visited.into(),
+        "distances".into(),
+        stats.distances.into(),
+        "layers".into(),
+        layers.into(),
+    ];
+
+    Ok(vec![
+        "results".into(),
+        results.into(),
+        "profile".into(),
+        profile.into(),
+    ]
+    .into())
+}
+
 #[cfg(not(test))]
 macro_rules! get_allocator {
     () => {
@@ -472,17 +1097,83 @@ macro_rules! get_allocator {
 }
 
 redis_module! {
-    name: "redisxann-hnsw",
+    // module configs are prefixed by the module name, e.g. hnsw.search_threads
+    name: "hnsw",
     version: 1,
     allocator: (get_allocator!(), get_allocator!()),
     data_types: [ HNSW_INDEX_REDIS_TYPE, HNSW_NODE_REDIS_TYPE ],
+    init: init,
+    info: add_info,
+    // key specs: the index key name arg is the 1st key, e.g. hnsw.{idx0},
+    // so redis checks the acl key patterns, and it hashes to the same slot as its node keys.
     commands: [
-        [format!("{}.index.create", PREFIX), create_index, "write", 0, 0, 0],
-        [format!("{}.index.get", PREFIX), get_index, "readonly", 0, 0, 0],
-        [format!("{}.index.del", PREFIX), delete_index, "write", 0, 0, 0],
-        [format!("{}.node.add", PREFIX), add_node, "write", 0, 0, 0],
-        [format!("{}.node.get", PREFIX), get_node, "readonly", 0, 0, 0],
-        [format!("{}.node.del", PREFIX), delete_node, "write", 0, 0, 0],
-        [format!("{}.search.kann", PREFIX), search_kann, "readonly", 0, 0, 0],
+        [format!("{}.index.create", PREFIX), create_index, "write deny-oom", 1, 1, 1],
+        [format!("{}.index.get", PREFIX), get_index, "readonly", 1, 1, 1],
+        [format!("{}.index.del", PREFIX), delete_index, "write", 1, 1, 1],
+        [format!("{}.index.list", PREFIX), list_index, "readonly", 0, 0, 0],
+        [format!("{}.index.migrate", PREFIX), migrate_index, "write", 1, 2, 1],
+        [format!("{}.key.rename", PREFIX), rename_key, "write", 1, 1, 1],
+        [format!("{}.node.add", PREFIX), add_node, "write deny-oom", 1, 1, 1],
+        [format!("{}.node.get", PREFIX), get_node, "readonly fast", 1, 1, 1],
+        [format!("{}.node.del", PREFIX), delete_node, "write", 1, 1, 1],
+        [format!("{}.node.scan", PREFIX), scan_node, "readonly", 1, 1, 1],
+        [format!("{}.search.kann", PREFIX), search_kann, "readonly", 1, 1, 1],
+        [format!("{}.search.profile", PREFIX), search_profile, "readonly", 1, 1, 1],
+    ],
+    event_handlers: [
+        [@GENERIC @EXPIRED @EVICTED @LOADED: events::on_keyspace_event],
+        [@STRING @LIST @SET @HASH @ZSET @STREAM: events::on_overwrite_event],
     ],
+    // CONFIG SET/GET hnsw.<name>, numeric configs are validated in [min, max] by redis
+    configurations: [
+        i64: [
+            ["search_threads", &*config::SEARCH_THREADS, config::DEFAULT_SEARCH_THREADS, 0, config::MAX_SEARCH_THREADS, ConfigurationFlags::DEFAULT, Some(Box::new(config::on_search_threads_changed))],
+        ],
+        bool: [
+            ["notify_events", &*config::NOTIFY_EVENTS, true, ConfigurationFlags::DEFAULT, None],
+        ],
+    ],
+}
+
+fn init(ctx: &Context, args: &[RedisString]) -> Status {
+    // node keys follow the renamed, moved and copied index keys by post notification jobs
+    if unsafe { raw::RedisModule_AddPostNotificationJob.is_none() } {
+        ctx.log_warning("hnsw needs redis 7.2+ for post notification jobs");
+        return Status::Err;
+    }
+
+    if let Err(e) = config::apply_module_args(ctx, args, |_, _| Ok(false)) {
+        ctx.log_warning(e.as_str());
+        return Status::Err;
+    }
+
+    if let Err(e) = events::subscribe_server_events(ctx) {
+        ctx.log_warning(format!("{}", e).as_str());
+        return Status::Err;
+    }
+
+    // searches run on the worker pool, search_threads 0 runs them on the main thread
+    pool::init(PREFIX, config::search_threads());
+
+    // rdb load callbacks check the IO errors, redis doesn't abort on them
+    unsafe {
+        raw::RedisModule_SetModuleOptions.unwrap()(
+            ctx.ctx,
+            raw::REDISMODULE_OPTIONS_HANDLE_IO_ERRORS as c_int,
+        );
+    }
+
+    Status::Ok
+}
+
+// add_info
+// INFO hnsw / INFO everything: the hnsw_redisxann section, see info::add_info
+fn add_info(ctx: &InfoContext, for_crash_report: bool) {
+    info::add_info(
+        ctx,
+        for_crash_report,
+        &HNSW_INDEX_REDIS_TYPE,
+        |index: &IndexRedis| index.node_count,
+        |db, index: &IndexRedis| index.mem_usage(db, info::INFO_MEM_SAMPLES).total(),
+    );
 }
@@ -1,19 +1,28 @@
 use redis_module::native_types::RedisType;
-use redis_module::{raw, RedisString, RedisValue};
+use redis_module::{logging, raw, RedisString, RedisValue};
 
 use num_traits::Float;
 use rand::prelude::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::From;
-use std::ffi::CString;
-use std::os::raw::c_void;
+use std::os::raw::{c_int, c_void};
 use std::ptr::NonNull;
+use std::sync::{Arc, RwLock};
 use std::{fmt, ptr};
 
-use hnswcore::core::{Index, Node, SearchResult};
+use hnswcore::core::{_Node, Index, Node, NodeWeak, SearchResult};
 use hnswcore::metrics;
 
-static INDEX_VERSION: i32 = 0;
+use crate::IndexArc;
+use modcore::defrag::Defrag;
+use modcore::digest::Digest;
+use modcore::rdb::{self, RdbResult};
+
+// rdb encoding versions, the value saved by an older encver is upgraded when it's loaded,
+// a newer encver fails the load instead of misreading the rdb.
+// encver 0: node names in index, graph nodes in node keys
+// encver 1: storage mode, compact storage saves the whole graph with node ids
+static INDEX_VERSION: i32 = 1;
 static NODE_VERSION: i32 = 0;
 
 impl From<IndexRedis> for Index<f32, f32> {
@@ -47,19 +56,21 @@ impl From<IndexRedis> for Index<f32, f32> {
 
 #[derive(Default, Clone)]
 pub struct IndexRedis {
-    pub name: String,               // index name
-    pub mfunc_kind: String,         // kind of the metric function
-    pub data_dim: usize,            // dimensionality of the data
-    pub m: usize,                   // out vertexs per node
-    pub m_max: usize,               // max number of vertexes per node
-    pub m_max_0: usize,             // max number of vertexes at layer 0
-    pub ef_construction: usize,     // size of dynamic candidate list
-    pub level_mult: f64,            // level generation factor
-    pub node_count: usize,          // count of nodes
-    pub max_layer: usize,           // idx of top layer
-    pub layers: Vec<Vec<String>>,   // distinct nodes in each layer
-    pub nodes: Vec<String>,         // set of node names
-    pub enterpoint: Option<String>, // string key to the enterpoint node
+    pub name: String,                     // index name
+    pub mfunc_kind: String,               // kind of the metric function
+    pub data_dim: usize,                  // dimensionality of the data
+    pub m: usize,                         // out vertexs per node
+    pub m_max: usize,                     // max number of vertexes per node
+    pub m_max_0: usize,                   // max number of vertexes at layer 0
+    pub ef_construction: usize,           // size of dynamic candidate list
+    pub level_mult: f64,                  // level generation factor
+    pub node_count: usize,                // count of nodes
+    pub max_layer: usize,                 // idx of top layer
+    pub layers: Vec<Vec<String>>,         // distinct nodes in each layer
+    pub nodes: Vec<String>,               // sorted set of node names
+    pub enterpoint: Option<String>,       // string key to the enterpoint node
+    pub copy_from: Option<(i32, String)>, // COPY source (db, index name), node keys are copied on copy_to event
+    pub graph: Option<IndexArc>, // compact storage, the whole graph lives in the index value
 }
 
 impl<T: Float, R: Float> From<Index<T, R>> for IndexRedis {
@@ -84,13 +95,388 @@ impl<T: Float, R: Float> From<Index<T, R>> for IndexRedis {
                         .collect::<Vec<String>>()
                 })
                 .collect(),
-            nodes: index.nodes.keys().cloned().collect::<Vec<String>>(),
+            nodes: {
+                // keep node names sorted for cursor scan
+                let mut nodes = index.nodes.keys().cloned().collect::<Vec<String>>();
+                nodes.sort_unstable();
+                nodes
+            },
             enterpoint: match &index.enterpoint {
                 Some(ep) => Some(ep.upgrade().read().name.clone()),
                 None => None,
             },
+            copy_from: None,
+            graph: None,
+        }
+    }
+}
+
+impl IndexRedis {
+    // new compact storage index, layers, nodes and enterpoint are kept in the graph
+    pub fn compact(index: Index<f32, f32>) -> Self {
+        let mut ir: IndexRedis = index.clone().into();
+        ir.layers = Vec::new();
+        ir.nodes = Vec::new();
+        ir.enterpoint = None;
+        ir.graph = Some(Arc::new(RwLock::new(index)));
+        ir
+    }
+
+    pub fn is_compact(&self) -> bool {
+        self.graph.is_some()
+    }
+
+    pub fn storage(&self) -> &'static str {
+        match self.graph {
+            Some(_) => "compact",
+            None => "keys",
         }
     }
+
+    // approximate memory usage of the index value and its graph, sample_size > 0 estimates
+    // the graph nodes by the first sample_size nodes. keys storage caches the graph out of
+    // the value, the cached graph of db is counted, node keys are counted by their own values.
+    pub fn mem_usage(&self, db: i32, sample_size: usize) -> MemUsage {
+        let names = self
+            .layers
+            .iter()
+            .map(|l| l.capacity() * std::mem::size_of::<String>())
+            .sum::<usize>()
+            + self
+                .layers
+                .iter()
+                .flatten()
+                .chain(self.nodes.iter())
+                .chain(self.enterpoint.iter())
+                .map(|n| n.capacity())
+                .sum::<usize>()
+            + self.nodes.capacity() * std::mem::size_of::<String>()
+            + self.name.capacity()
+            + self.mfunc_kind.capacity();
+        let mut usage = MemUsage {
+            names: std::mem::size_of::<Self>() + names,
+            ..Default::default()
+        };
+
+        let graph = match &self.graph {
+            Some(graph) => Some(graph.clone()),
+            None => crate::INDICES
+                .try_read()
+                .ok()
+                .and_then(|indices| indices.get(&(db, self.name.clone())).cloned()),
+        };
+        if let Some(graph) = graph {
+            // the graph is written on the main thread only, a failed read is skipped
+            if let Ok(graph) = graph.try_read() {
+                usage.add(&MemUsage::of_graph(&graph, sample_size));
+            }
+        }
+        usage
+    }
+
+    // the index renamed as index key name, with node names prefixed by the new name
+    pub fn renamed(&self, name: &str) -> Self {
+        let rename = |n: &String| rename_node(n, &self.name, name);
+        IndexRedis {
+            name: name.to_string(),
+            mfunc_kind: self.mfunc_kind.clone(),
+            data_dim: self.data_dim,
+            m: self.m,
+            m_max: self.m_max,
+            m_max_0: self.m_max_0,
+            ef_construction: self.ef_construction,
+            level_mult: self.level_mult,
+            node_count: self.node_count,
+            max_layer: self.max_layer,
+            layers: self
+                .layers
+                .iter()
+                .map(|l| l.iter().map(rename).collect::<Vec<String>>())
+                .collect(),
+            nodes: self.nodes.iter().map(rename).collect(),
+            enterpoint: self.enterpoint.as_ref().map(rename),
+            copy_from: None,
+            graph: None,
+        }
+        .with_graph_renamed(self, name)
+    }
+
+    // compact storage graph is rebuilt with renamed nodes, so it isn't shared with self
+    fn with_graph_renamed(mut self, from: &IndexRedis, name: &str) -> Self {
+        if let Some(graph) = &from.graph {
+            let cg = CompactGraph::from(&*graph.read().unwrap()).renamed(&from.name, name);
+            self.graph = Some(Arc::new(RwLock::new(cg.into_index(&self))));
+        }
+        self
+    }
+}
+
+// MemUsage
+// approximate memory usage of an index in bytes by part
+#[derive(Default, Debug)]
+pub struct MemUsage {
+    pub graph: usize,   // nodes, neighbor links and layers
+    pub vectors: usize, // node vectors
+    pub names: usize,   // node names and the index value
+}
+
+impl MemUsage {
+    pub fn total(&self) -> usize {
+        self.graph + self.vectors + self.names
+    }
+
+    fn add(&mut self, other: &MemUsage) {
+        self.graph += other.graph;
+        self.vectors += other.vectors;
+        self.names += other.names;
+    }
+
+    // node names are counted twice, as the node name and the nodes map key
+    fn of_graph(index: &Index<f32, f32>, sample_size: usize) -> Self {
+        let count = index.nodes.len();
+        let sampled = match sample_size {
+            0 => count,
+            n => n.min(count),
+        };
+        let mut usage = MemUsage::default();
+        for (name, node) in index.nodes.iter().take(sampled) {
+            let n = node.read();
+            usage.names += name.capacity() + n.name.capacity();
+            usage.vectors += n.data.capacity() * std::mem::size_of::<f32>();
+            usage.graph += std::mem::size_of::<RwLock<_Node<f32>>>()
+                + n.neighbors.capacity() * std::mem::size_of::<Vec<NodeWeak<f32>>>()
+                + n.neighbors.iter().map(|l| l.capacity()).sum::<usize>()
+                    * std::mem::size_of::<NodeWeak<f32>>();
+        }
+        if sampled > 0 && sampled < count {
+            usage.names = usage.names * count / sampled;
+            usage.vectors = usage.vectors * count / sampled;
+            usage.graph = usage.graph * count / sampled;
+        }
+
+        // hash tables with a control byte per bucket
+        usage.graph += index.nodes.capacity() * (std::mem::size_of::<(String, Node<f32>)>() + 1)
+            + index
+                .layers
+                .iter()
+                .map(|l| l.capacity() * (std::mem::size_of::<NodeWeak<f32>>() + 1))
+                .sum::<usize>();
+        usage
+    }
+}
+
+impl From<MemUsage> for Vec<RedisValue> {
+    fn from(usage: MemUsage) -> Self {
+        vec![
+            "graph_mem_usage".into(),
+            usage.graph.into(),
+            "vectors_mem_usage".into(),
+            usage.vectors.into(),
+            "names_mem_usage".into(),
+            usage.names.into(),
+            "mem_usage".into(),
+            usage.total().into(),
+        ]
+    }
+}
+
+// CompactGraph
+// the rdb encoding of the compact storage graph with integer node ids and contiguous vector storage,
+// node id is the position of node name in sorted names.
+// note: it's only the rdb layout, the graph is kept in memory as the hnswcore index.
+#[derive(Default)]
+pub struct CompactGraph {
+    pub names: Vec<String>,            // sorted node names
+    pub data: Vec<f32>,                // node vectors, data_dim floats per node
+    pub neighbors: Vec<Vec<Vec<u64>>>, // neighbor ids of each node in each layer
+    pub layers: Vec<Vec<u64>>,         // distinct node ids in each layer
+    pub enterpoint: Option<u64>,       // enterpoint node id
+}
+
+impl From<&Index<f32, f32>> for CompactGraph {
+    fn from(index: &Index<f32, f32>) -> Self {
+        let mut names = index.nodes.keys().cloned().collect::<Vec<String>>();
+        names.sort_unstable();
+        let ids = names
+            .iter()
+            .enumerate()
+            .map(|(id, name)| (name.as_str(), id as u64))
+            .collect::<HashMap<&str, u64>>();
+
+        let mut data = Vec::with_capacity(names.len() * index.data_dim);
+        let mut neighbors = Vec::with_capacity(names.len());
+        for name in &names {
+            let node = index.nodes.get(name).unwrap().read();
+            data.extend_from_slice(&node.data);
+            neighbors.push(
+                node.neighbors
+                    .iter()
+                    .map(|l| {
+                        l.iter()
+                            .map(|n| ids[n.upgrade().read().name.as_str()])
+                            .collect::<Vec<u64>>()
+                    })
+                    .collect::<Vec<Vec<u64>>>(),
+            );
+        }
+
+        let layers = index
+            .layers
+            .iter()
+            .map(|l| {
+                let mut layer = l
+                    .iter()
+                    .map(|n| ids[n.upgrade().read().name.as_str()])
+                    .collect::<Vec<u64>>();
+                layer.sort_unstable();
+                layer
+            })
+            .collect();
+        let enterpoint = index
+            .enterpoint
+            .as_ref()
+            .map(|ep| ids[ep.upgrade().read().name.as_str()]);
+
+        CompactGraph {
+            names,
+            data,
+            neighbors,
+            layers,
+            enterpoint,
+        }
+    }
+}
+
+impl CompactGraph {
+    // the graph of the renamed index, node names are prefixed by the new name
+    pub fn renamed(mut self, old_index_name: &str, new_index_name: &str) -> Self {
+        for name in self.names.iter_mut() {
+            *name = rename_node(name, old_index_name, new_index_name);
+        }
+        self
+    }
+
+    // check node ids and vector lengths, e.g. after rdb load
+    pub fn validate(&self, data_dim: usize) -> Result<(), String> {
+        let n = self.names.len() as u64;
+        if self.data.len() != self.names.len() * data_dim {
+            return Err(format!(
+                "data len {} != {} nodes * dim {}",
+                self.data.len(),
+                n,
+                data_dim
+            ));
+        }
+        if self.neighbors.len() != self.names.len() {
+            return Err(format!(
+                "neighbors len {} != {} nodes",
+                self.neighbors.len(),
+                n
+            ));
+        }
+        let ids = self
+            .neighbors
+            .iter()
+            .flatten()
+            .chain(self.layers.iter())
+            .flatten()
+            .chain(self.enterpoint.iter());
+        for id in ids {
+            if *id >= n {
+                return Err(format!("node id {} out of range {}", id, n));
+            }
+        }
+        Ok(())
+    }
+
+    // build the graph of the index, the ids must be valid
+    pub fn into_index(self, ir: &IndexRedis) -> Index<f32, f32> {
+        let mut index: Index<f32, f32> = ir.clone().into();
+        let dim = index.data_dim;
+
+        let nodes = self
+            .names
+            .iter()
+            .enumerate()
+            .map(|(id, name)| {
+                let data = &self.data[id * dim..(id + 1) * dim];
+                Node::new(name, data, index.m_max_0)
+            })
+            .collect::<Vec<Node<f32>>>();
+
+        for (node, layers) in nodes.iter().zip(self.neighbors) {
+            let mut target = node.write();
+            for layer in layers {
+                target.neighbors.push(
+                    layer
+                        .into_iter()
+                        .map(|id| nodes[id as usize].downgrade())
+                        .collect(),
+                );
+            }
+        }
+
+        index.layers = self
+            .layers
+            .into_iter()
+            .map(|l| {
+                l.into_iter()
+                    .map(|id| nodes[id as usize].downgrade())
+                    .collect::<HashSet<_>>()
+            })
+            .collect();
+        index.enterpoint = self.enterpoint.map(|id| nodes[id as usize].downgrade());
+        index.node_count = nodes.len();
+        index.nodes = self.names.into_iter().zip(nodes).collect();
+        index
+    }
+}
+
+// is_hnsw_key
+// index and node key names start with hnsw., e.g. hnsw.{idx0}, hnsw.{idx0}.n1, legacy hnsw.idx0
+pub fn is_hnsw_key(key: &[u8]) -> bool {
+    key.strip_prefix(crate::PREFIX.as_bytes())
+        .map_or(false, |rest| rest.starts_with(b"."))
+}
+
+// node_index_name
+// index key name of the hash tagged node key name, e.g. hnsw.{idx0} of hnsw.{idx0}.n1,
+// None if the node key isn't hash tagged, e.g. the legacy node key hnsw.idx0.n1
+pub fn node_index_name(node_name: &str) -> Option<&str> {
+    let i = node_name.find('}')?;
+    match node_name[i + 1..].starts_with('.') {
+        true => Some(&node_name[..=i]),
+        false => None,
+    }
+}
+
+// renamed_index_names
+// index names of the node key renamed from from_node to to_node with the same short name,
+// split at the first '.' of the common suffix, e.g. hnsw.{idx0}.n1, hnsw.idx1.n1 -> hnsw.{idx0}, hnsw.idx1
+pub fn renamed_index_names<'a>(from_node: &'a str, to_node: &'a str) -> Option<(&'a str, &'a str)> {
+    let common = from_node
+        .bytes()
+        .rev()
+        .zip(to_node.bytes().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let from_bytes = from_node.as_bytes();
+    let i = (from_node.len() - common..from_node.len()).find(|&i| from_bytes[i] == b'.')?;
+    let j = to_node.len() - (from_node.len() - i);
+    if i == 0 || j == 0 {
+        return None;
+    }
+    Some((&from_node[..i], &to_node[..j]))
+}
+
+// node key names are prefixed with the index key name, e.g. hnsw.{idx0}.n1
+pub fn rename_node(node_name: &str, old_index_name: &str, new_index_name: &str) -> String {
+    match node_name.strip_prefix(old_index_name) {
+        Some(short_name) if short_name.starts_with('.') => {
+            format!("{}{}", new_index_name, short_name)
+        }
+        _ => node_name.to_string(),
+    }
 }
 
 impl fmt::Debug for IndexRedis {
@@ -105,6 +491,7 @@ impl fmt::Debug for IndexRedis {
              level_mult: {}, \
              node_count: {}, \
              max_layer: {}, \
+             storage: {}, \
              enterpoint: {}",
             self.name,
             self.mfunc_kind,
@@ -114,6 +501,7 @@ impl fmt::Debug for IndexRedis {
             self.level_mult,
             self.node_count,
             self.max_layer,
+            self.storage(),
             match &self.enterpoint {
                 Some(ep) => ep.as_str(),
                 None => "null",
@@ -122,7 +510,7 @@ impl fmt::Debug for IndexRedis {
     }
 }
 
-impl From<IndexRedis> for RedisValue {
+impl From<IndexRedis> for Vec<RedisValue> {
     fn from(index: IndexRedis) -> Self {
         let mut reply: Vec<RedisValue> = Vec::new();
 
@@ -150,10 +538,13 @@ impl From<IndexRedis> for RedisValue {
         reply.push("max_layer".into());
         reply.push(index.max_layer.into());
 
+        reply.push("storage".into());
+        reply.push(index.storage().into());
+
         reply.push("enterpoint".into());
         reply.push(index.enterpoint.into());
 
-        reply.into()
+        reply
     }
 }
 
@@ -170,7 +561,7 @@ pub static HNSW_INDEX_REDIS_TYPE: RedisType = RedisType::new(
 
         // Currently unused by Redis
         mem_usage: None,
-        digest: None,
+        digest: Some(digest_index),
 
         // Aux data
         aux_load: None,
@@ -178,18 +569,199 @@ pub static HNSW_INDEX_REDIS_TYPE: RedisType = RedisType::new(
         aux_save2: None,
         aux_save_triggers: 0,
 
-        free_effort: None,
+        free_effort: Some(free_effort_index),
         unlink: None,
         copy: None,
-        defrag: None,
+        defrag: Some(defrag_index),
 
-        copy2: None,
+        copy2: Some(copy_index),
         free_effort2: None,
-        mem_usage2: None,
-        unlink2: None,
+        mem_usage2: Some(mem_usage_index),
+        unlink2: Some(unlink_index),
     },
 );
 
+// COPY src dst [DB db] [REPLACE]
+// the index value is copied with the dst node names, node keys are copied by the post notification job
+// of the copy_to keyspace event, so the copied index doesn't share node keys with the source index.
+// the dst key is replaced after the copy, it's marked to keep the node names of a replaced dst index.
+unsafe extern "C" fn copy_index(
+    ctx: *mut raw::RedisModuleKeyOptCtx,
+    value: *const c_void,
+) -> *mut c_void {
+    let index = unsafe { &*value.cast::<IndexRedis>() };
+    let from_db = raw::RedisModule_GetDbIdFromOptCtx.unwrap()(ctx);
+    let to_key = raw::RedisModule_GetToKeyNameFromOptCtx.unwrap()(ctx);
+    let to_name = match RedisString::from_ptr(to_key) {
+        Ok(name) => name.to_owned(),
+        Err(e) => {
+            logging::log_warning(format!("copy Index: {} err {}", index.name, e));
+            return ptr::null_mut();
+        }
+    };
+
+    // keys which aren't hnsw keys don't get the copy_to keyspace event
+    let mut copied = index.renamed(&to_name);
+    if is_hnsw_key(to_name.as_bytes()) {
+        let to_db = raw::RedisModule_GetToDbIdFromOptCtx.unwrap()(ctx);
+        crate::events::copying(to_db, &to_name);
+        copied.copy_from = Some((from_db, index.name.clone()));
+    }
+    Box::into_raw(Box::new(copied)).cast::<c_void>()
+}
+
+// digest of the options, the sorted node names and the node vectors,
+// graph links, layers and the enterpoint aren't digested: node levels are seeded by the node names,
+// but deletes pick a new enterpoint in hash set order, so replicas can link the same nodes differently.
+// node vectors of keys storage are digested with the node keys.
+unsafe extern "C" fn digest_index(md: *mut raw::RedisModuleDigest, value: *mut c_void) {
+    let index = unsafe { &*value.cast::<IndexRedis>() };
+    let digest = Digest::new(md);
+
+    digest.add_str(&index.name);
+    digest.add_str(&index.mfunc_kind);
+    digest.add_u64(index.data_dim as u64);
+    digest.add_u64(index.m as u64);
+    digest.add_u64(index.m_max as u64);
+    digest.add_u64(index.m_max_0 as u64);
+    digest.add_u64(index.ef_construction as u64);
+    digest.add_bytes(&index.level_mult.to_le_bytes());
+    digest.add_u64(index.node_count as u64);
+    digest.add_str(index.storage());
+    digest.end_sequence();
+
+    match &index.graph {
+        Some(graph) => {
+            let graph = graph.read().unwrap();
+            let mut names = graph.nodes.keys().collect::<Vec<&String>>();
+            names.sort_unstable();
+            for name in names {
+                digest.add_str(name);
+                digest_node_data(&digest, &graph.nodes[name].read().data);
+                digest.end_sequence();
+            }
+        }
+        None => {
+            let mut names = index.nodes.iter().collect::<Vec<&String>>();
+            names.sort_unstable();
+            for name in names {
+                digest.add_str(name);
+            }
+            digest.end_sequence();
+        }
+    }
+}
+
+// node vector
+fn digest_node_data(digest: &Digest, data: &[f32]) {
+    for datum in data.iter() {
+        digest.add_bytes(&datum.to_le_bytes());
+    }
+}
+
+// UNLINK (and DEL with lazyfree-lazy-user-del) frees the index in the lazyfree thread
+// if the effort is greater than the redis LAZYFREE_THRESHOLD, the effort is the node count.
+unsafe extern "C" fn free_effort_index(
+    _key: *mut raw::RedisModuleString,
+    value: *const c_void,
+) -> usize {
+    let index = unsafe { &*value.cast::<IndexRedis>() };
+    index.node_count.max(1)
+}
+
+// the index value is removed from the keyspace (deleted or overwritten) before it's freed maybe in
+// the lazyfree thread, unregister it and drop its cached graph on the main thread.
+unsafe extern "C" fn unlink_index(ctx: *mut raw::RedisModuleKeyOptCtx, value: *const c_void) {
+    let index = unsafe { &*value.cast::<IndexRedis>() };
+    let db = raw::RedisModule_GetDbIdFromOptCtx.unwrap()(ctx);
+    let key = raw::RedisModule_GetKeyNameFromOptCtx.unwrap()(ctx);
+    if let Ok(name) = RedisString::from_ptr(key) {
+        crate::events::unlinked(db, name, index);
+        crate::registry::remove(db, name);
+        let graph = crate::INDICES
+            .write()
+            .unwrap()
+            .remove(&(db, name.to_string()));
+        if let Some(graph) = graph {
+            crate::drop_graph(graph);
+        }
+    }
+}
+
+// activedefrag moves the value and node names, compact storage moves the graph node vectors and
+// neighbor lists too, the cursor is the position of the next node.
+// note: workers search the graph with the read lock, a busy graph is skipped until the next cycle
+unsafe extern "C" fn defrag_index(
+    ctx: *mut raw::RedisModuleDefragCtx,
+    _key: *mut raw::RedisModuleString,
+    value: *mut *mut c_void,
+) -> c_int {
+    let defrag = Defrag::new(ctx);
+    let cursor = match defrag.cursor() {
+        Some(cursor) => cursor as usize,
+        None => {
+            defrag.value(value);
+            let index = unsafe { &mut *(*value).cast::<IndexRedis>() };
+            defrag.string(&mut index.name);
+            defrag.vec(&mut index.nodes);
+            defrag.vec(&mut index.layers);
+            for layer in index.layers.iter_mut() {
+                defrag.vec(layer);
+            }
+            0
+        }
+    };
+
+    let index = unsafe { &mut *(*value).cast::<IndexRedis>() };
+    let graph = match &index.graph {
+        Some(graph) => graph.clone(),
+        None => {
+            let names = index
+                .nodes
+                .iter_mut()
+                .chain(index.layers.iter_mut().flatten());
+            for (i, name) in names.enumerate().skip(cursor) {
+                defrag.string(name);
+                if defrag.should_stop() {
+                    defrag.set_cursor(i as u64 + 1);
+                    return 1;
+                }
+            }
+            return 0;
+        }
+    };
+    let graph = match graph.try_write() {
+        Ok(graph) => graph,
+        Err(_) => return 0,
+    };
+    // nodes changed between steps may be skipped or moved twice, both are harmless
+    for (i, node) in graph.nodes.values().enumerate().skip(cursor) {
+        let mut node = node.write();
+        defrag.string(&mut node.name);
+        defrag.vec(&mut node.data);
+        defrag.vec(&mut node.neighbors);
+        for neighbors in node.neighbors.iter_mut() {
+            defrag.vec(neighbors);
+        }
+        if defrag.should_stop() {
+            defrag.set_cursor(i as u64 + 1);
+            return 1;
+        }
+    }
+    0
+}
+
+// MEMORY USAGE key [SAMPLES count]
+unsafe extern "C" fn mem_usage_index(
+    ctx: *mut raw::RedisModuleKeyOptCtx,
+    value: *const c_void,
+    sample_size: usize,
+) -> usize {
+    let index = unsafe { &*value.cast::<IndexRedis>() };
+    let db = raw::RedisModule_GetDbIdFromOptCtx.unwrap()(ctx);
+    index.mem_usage(db, sample_size).total()
+}
+
 unsafe extern "C" fn free_index(value: *mut c_void) {
     if value.is_null() {
         // on Redis 6.0 we might get a NULL value here, so we need to handle it.
@@ -198,67 +770,81 @@ unsafe extern "C" fn free_index(value: *mut c_void) {
     drop(Box::from_raw(value as *mut IndexRedis));
 }
 
-unsafe extern "C" fn load_index(rdb: *mut raw::RedisModuleIO, version: i32) -> *mut c_void {
-    if version != INDEX_VERSION {
-        return ptr::null_mut() as *mut c_void;
-    }
-
-    let mut index = Box::new(IndexRedis::default());
-
-    let name = raw::RedisModule_LoadString.unwrap()(rdb);
-    index.name = redis_module::RedisString::from_ptr(name)
-        .unwrap()
-        .to_owned();
-
-    let mfunc_kind = raw::RedisModule_LoadString.unwrap()(rdb);
-    index.mfunc_kind = redis_module::RedisString::from_ptr(mfunc_kind)
-        .unwrap()
-        .to_owned();
-
-    index.data_dim = raw::RedisModule_LoadUnsigned.unwrap()(rdb) as usize;
-    index.m = raw::RedisModule_LoadUnsigned.unwrap()(rdb) as usize;
-    index.m_max = raw::RedisModule_LoadUnsigned.unwrap()(rdb) as usize;
-    index.m_max_0 = raw::RedisModule_LoadUnsigned.unwrap()(rdb) as usize;
-    index.ef_construction = raw::RedisModule_LoadUnsigned.unwrap()(rdb) as usize;
-    index.level_mult = raw::RedisModule_LoadDouble.unwrap()(rdb);
-    index.node_count = raw::RedisModule_LoadUnsigned.unwrap()(rdb) as usize;
-    index.max_layer = raw::RedisModule_LoadUnsigned.unwrap()(rdb) as usize;
-
-    let num_layers = raw::RedisModule_LoadUnsigned.unwrap()(rdb) as usize;
-    index.layers = Vec::with_capacity(num_layers);
-    for l in 0..num_layers {
-        let num_nodes = raw::RedisModule_LoadUnsigned.unwrap()(rdb) as usize;
-        index.layers.push(Vec::with_capacity(num_nodes));
-        for _n in 0..num_nodes {
-            let node_name = raw::RedisModule_LoadString.unwrap()(rdb);
-            index.layers[l].push(
-                redis_module::RedisString::from_ptr(node_name)
-                    .unwrap()
-                    .to_owned(),
-            );
+unsafe extern "C" fn load_index(rdb: *mut raw::RedisModuleIO, encver: i32) -> *mut c_void {
+    if encver > INDEX_VERSION {
+        logging::log_warning(format!(
+            "load hnsw index err: rdb encver {} is newer than module encver {}, upgrade the module",
+            encver, INDEX_VERSION
+        ));
+        return ptr::null_mut();
+    }
+    match load_index_encver(rdb, encver) {
+        Ok(index) => Box::into_raw(Box::new(index)) as *mut c_void,
+        Err(e) => {
+            logging::log_warning(format!("load hnsw index (encver {}) err {}", encver, e));
+            ptr::null_mut()
         }
     }
+}
 
-    let num_nodes = raw::RedisModule_LoadUnsigned.unwrap()(rdb) as usize;
-    index.nodes = Vec::with_capacity(num_nodes);
-    for _n in 0..num_nodes {
-        let node_name = raw::RedisModule_LoadString.unwrap()(rdb);
-        index.nodes.push(
-            redis_module::RedisString::from_ptr(node_name)
-                .unwrap()
-                .to_owned(),
-        );
+// load the index fields saved by encver, then upgrade them to the current version
+unsafe fn load_index_encver(rdb: *mut raw::RedisModuleIO, encver: i32) -> RdbResult<IndexRedis> {
+    let mut index = IndexRedis::default();
+    index.name = rdb::load_string(rdb)?;
+    index.mfunc_kind = rdb::load_string(rdb)?;
+    index.data_dim = rdb::load_unsigned(rdb)? as usize;
+    index.m = rdb::load_unsigned(rdb)? as usize;
+    index.m_max = rdb::load_unsigned(rdb)? as usize;
+    index.m_max_0 = rdb::load_unsigned(rdb)? as usize;
+    index.ef_construction = rdb::load_unsigned(rdb)? as usize;
+    index.level_mult = rdb::load_double(rdb)?;
+    index.node_count = rdb::load_unsigned(rdb)? as usize;
+    index.max_layer = rdb::load_unsigned(rdb)? as usize;
+    if index.data_dim == 0 {
+        return Err(format!("Index: {} data dim is 0", index.name));
     }
 
-    let ep = raw::RedisModule_LoadString.unwrap()(rdb);
-    let ep = redis_module::RedisString::from_ptr(ep).unwrap().to_owned();
+    let compact = match encver {
+        // upgrade encver 0: no storage mode, graph nodes are in node keys
+        0 => false,
+        // encver 1: storage mode
+        _ => rdb::load_unsigned(rdb)? == 1,
+    };
+    if compact {
+        let cg = load_compact_graph(rdb)?;
+        cg.validate(index.data_dim)
+            .map_err(|e| format!("compact Index: {} err {}", index.name, e))?;
+        let graph = cg.into_index(&index);
+        index.graph = Some(Arc::new(RwLock::new(graph)));
+        return Ok(index);
+    }
+
+    let num_layers = rdb::load_len(rdb)?;
+    index.layers = rdb::with_capacity(num_layers);
+    for _l in 0..num_layers {
+        index.layers.push(load_names(rdb)?);
+    }
+
+    index.nodes = load_names(rdb)?;
+    // rdb saved by old version maybe unsorted
+    index.nodes.sort_unstable();
+
+    let ep = rdb::load_string(rdb)?;
     index.enterpoint = match ep.as_str() {
         "null" => None,
         _ => Some(ep),
     };
 
-    let index: *mut c_void = Box::into_raw(index) as *mut c_void;
-    index
+    Ok(index)
+}
+
+unsafe fn load_names(rdb: *mut raw::RedisModuleIO) -> RdbResult<Vec<String>> {
+    let num_names = rdb::load_len(rdb)?;
+    let mut names = rdb::with_capacity(num_names);
+    for _n in 0..num_names {
+        names.push(rdb::load_string(rdb)?);
+    }
+    Ok(names)
 }
 
 unsafe extern "C" fn save_index(rdb: *mut raw::RedisModuleIO, value: *mut c_void) {
@@ -268,11 +854,8 @@ unsafe extern "C" fn save_index(rdb: *mut raw::RedisModuleIO, value: *mut c_void
 
     let ctx = ptr::null_mut();
 
-    let name_cstring = CString::new(index.name.as_str()).unwrap();
-    raw::save_string(rdb, name_cstring.to_str().unwrap());
-
-    let mfunc_kind_cstring = CString::new(index.mfunc_kind.as_str()).unwrap();
-    raw::save_string(rdb, mfunc_kind_cstring.to_str().unwrap());
+    raw::save_string(rdb, index.name.as_str());
+    raw::save_string(rdb, index.mfunc_kind.as_str());
 
     raw::RedisModule_SaveUnsigned.unwrap()(rdb, index.data_dim as u64);
     raw::RedisModule_SaveUnsigned.unwrap()(rdb, index.m as u64);
@@ -283,6 +866,13 @@ unsafe extern "C" fn save_index(rdb: *mut raw::RedisModuleIO, value: *mut c_void
     raw::RedisModule_SaveUnsigned.unwrap()(rdb, index.node_count as u64);
     raw::RedisModule_SaveUnsigned.unwrap()(rdb, index.max_layer as u64);
 
+    if let Some(graph) = &index.graph {
+        raw::RedisModule_SaveUnsigned.unwrap()(rdb, 1);
+        save_compact_graph(rdb, &graph.read().unwrap());
+        return;
+    }
+    raw::RedisModule_SaveUnsigned.unwrap()(rdb, 0);
+
     raw::RedisModule_SaveUnsigned.unwrap()(rdb, index.layers.len() as u64);
     for layer in index.layers.as_slice() {
         raw::RedisModule_SaveUnsigned.unwrap()(rdb, layer.len() as u64);
@@ -306,12 +896,138 @@ unsafe extern "C" fn save_index(rdb: *mut raw::RedisModuleIO, value: *mut c_void
     raw::RedisModule_SaveString.unwrap()(rdb, ep.inner);
 }
 
-#[derive(Default)]
+unsafe fn load_ids(rdb: *mut raw::RedisModuleIO) -> RdbResult<Vec<u64>> {
+    let num_ids = rdb::load_len(rdb)?;
+    let mut ids = rdb::with_capacity(num_ids);
+    for _i in 0..num_ids {
+        ids.push(rdb::load_unsigned(rdb)?);
+    }
+    Ok(ids)
+}
+
+unsafe fn save_ids(rdb: *mut raw::RedisModuleIO, ids: &[u64]) {
+    raw::RedisModule_SaveUnsigned.unwrap()(rdb, ids.len() as u64);
+    for id in ids {
+        raw::RedisModule_SaveUnsigned.unwrap()(rdb, *id);
+    }
+}
+
+// compact graph rdb layout:
+// node names, node vectors as one little endian f32 buffer,
+// neighbor ids of each node, layer ids, enterpoint id + 1 (0 is null)
+unsafe fn load_compact_graph(rdb: *mut raw::RedisModuleIO) -> RdbResult<CompactGraph> {
+    let mut cg = CompactGraph::default();
+
+    cg.names = load_names(rdb)?;
+    let num_nodes = cg.names.len();
+
+    let buf = rdb::load_buffer(rdb)?;
+    if buf.len() % std::mem::size_of::<f32>() != 0 {
+        return Err(format!(
+            "node vectors buffer len {} isn't f32 aligned",
+            buf.len()
+        ));
+    }
+    cg.data = buf
+        .chunks_exact(std::mem::size_of::<f32>())
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+
+    cg.neighbors = Vec::with_capacity(num_nodes);
+    for _n in 0..num_nodes {
+        let num_layers = rdb::load_len(rdb)?;
+        let mut layers = rdb::with_capacity(num_layers);
+        for _l in 0..num_layers {
+            layers.push(load_ids(rdb)?);
+        }
+        cg.neighbors.push(layers);
+    }
+
+    let num_layers = rdb::load_len(rdb)?;
+    cg.layers = rdb::with_capacity(num_layers);
+    for _l in 0..num_layers {
+        cg.layers.push(load_ids(rdb)?);
+    }
+
+    cg.enterpoint = match rdb::load_unsigned(rdb)? {
+        0 => None,
+        id => Some(id - 1),
+    };
+
+    Ok(cg)
+}
+
+// save the graph in the compact graph rdb layout without building a CompactGraph,
+// node ids are the positions of the sorted node names, vectors are written to the buffer once.
+unsafe fn save_compact_graph(rdb: *mut raw::RedisModuleIO, index: &Index<f32, f32>) {
+    let ctx = ptr::null_mut();
+
+    let mut names = index.nodes.keys().collect::<Vec<&String>>();
+    names.sort_unstable();
+    let ids = names
+        .iter()
+        .enumerate()
+        .map(|(id, name)| (name.as_str(), id as u64))
+        .collect::<HashMap<&str, u64>>();
+    let node_id = |n: &NodeWeak<f32>| ids[n.upgrade().read().name.as_str()];
+
+    raw::RedisModule_SaveUnsigned.unwrap()(rdb, names.len() as u64);
+    for n in names.iter() {
+        let s = RedisString::create(NonNull::new(ctx), n.as_str());
+        raw::RedisModule_SaveString.unwrap()(rdb, s.inner);
+    }
+
+    let mut buf = Vec::with_capacity(names.len() * index.data_dim * std::mem::size_of::<f32>());
+    for n in names.iter() {
+        for d in index.nodes[*n].read().data.iter() {
+            buf.extend_from_slice(&d.to_le_bytes());
+        }
+    }
+    raw::save_slice(rdb, &buf);
+
+    for n in names.iter() {
+        let node = index.nodes[*n].read();
+        raw::RedisModule_SaveUnsigned.unwrap()(rdb, node.neighbors.len() as u64);
+        for l in node.neighbors.iter() {
+            save_ids(rdb, &l.iter().map(node_id).collect::<Vec<u64>>());
+        }
+    }
+
+    raw::RedisModule_SaveUnsigned.unwrap()(rdb, index.layers.len() as u64);
+    for l in index.layers.iter() {
+        let mut layer = l.iter().map(node_id).collect::<Vec<u64>>();
+        layer.sort_unstable();
+        save_ids(rdb, &layer);
+    }
+
+    let ep = index.enterpoint.as_ref().map(node_id);
+    raw::RedisModule_SaveUnsigned.unwrap()(rdb, ep.map_or(0, |id| id + 1));
+}
+
+#[derive(Default, Clone)]
 pub struct NodeRedis {
     pub data: Vec<f32>,
     pub neighbors: Vec<Vec<String>>, // vector of neighbor node names
 }
 
+impl NodeRedis {
+    // the node with neighbor names of the renamed index
+    pub fn renamed(&self, old_index_name: &str, new_index_name: &str) -> Self {
+        NodeRedis {
+            data: self.data.clone(),
+            neighbors: self
+                .neighbors
+                .iter()
+                .map(|l| {
+                    l.iter()
+                        .map(|n| rename_node(n, old_index_name, new_index_name))
+                        .collect::<Vec<String>>()
+                })
+                .collect(),
+        }
+    }
+}
+
 impl From<&Node<f32>> for NodeRedis {
     fn from(node: &Node<f32>) -> Self {
         let r = node.read();
@@ -388,7 +1104,7 @@ pub static HNSW_NODE_REDIS_TYPE: RedisType = RedisType::new(
 
         // Currently unused by Redis
         mem_usage: None,
-        digest: None,
+        digest: Some(digest_node),
 
         // Aux data
         aux_load: None,
@@ -399,50 +1115,125 @@ pub static HNSW_NODE_REDIS_TYPE: RedisType = RedisType::new(
         free_effort: None,
         unlink: None,
         copy: None,
-        defrag: None,
+        defrag: Some(defrag_node),
 
-        copy2: None,
+        copy2: Some(copy_node),
         free_effort2: None,
-        mem_usage2: None,
+        mem_usage2: Some(mem_usage_node),
         unlink2: None,
     },
 );
 
+// COPY src dst [DB db] [REPLACE]
+// neighbor names are renamed from the src index to the dst index of the hash tagged node key names,
+// so the node keys copied with a copied index (replicated as COPY) link the dst index nodes.
+unsafe extern "C" fn copy_node(
+    ctx: *mut raw::RedisModuleKeyOptCtx,
+    value: *const c_void,
+) -> *mut c_void {
+    let node = unsafe { &*value.cast::<NodeRedis>() };
+    let from_key = raw::RedisModule_GetKeyNameFromOptCtx.unwrap()(ctx);
+    let to_key = raw::RedisModule_GetToKeyNameFromOptCtx.unwrap()(ctx);
+    let copied = match (
+        RedisString::from_ptr(from_key),
+        RedisString::from_ptr(to_key),
+    ) {
+        (Ok(from_name), Ok(to_name)) => {
+            match (node_index_name(from_name), node_index_name(to_name)) {
+                (Some(from_index), Some(to_index)) => node.renamed(from_index, to_index),
+                _ => node.clone(),
+            }
+        }
+        _ => node.clone(),
+    };
+    Box::into_raw(Box::new(copied)).cast::<c_void>()
+}
+
+unsafe extern "C" fn digest_node(md: *mut raw::RedisModuleDigest, value: *mut c_void) {
+    let node = unsafe { &*value.cast::<NodeRedis>() };
+    let digest = Digest::new(md);
+    digest_node_data(&digest, &node.data);
+    digest.end_sequence();
+}
+
+// a node is small, it's defragged in one step
+unsafe extern "C" fn defrag_node(
+    ctx: *mut raw::RedisModuleDefragCtx,
+    _key: *mut raw::RedisModuleString,
+    value: *mut *mut c_void,
+) -> c_int {
+    let defrag = Defrag::new(ctx);
+    defrag.value(value);
+    let node = unsafe { &mut *(*value).cast::<NodeRedis>() };
+    defrag.vec(&mut node.data);
+    defrag.vec(&mut node.neighbors);
+    for neighbors in node.neighbors.iter_mut() {
+        defrag.vec(neighbors);
+        for name in neighbors.iter_mut() {
+            defrag.string(name);
+        }
+    }
+    0
+}
+
+// node vector and neighbor names
+unsafe extern "C" fn mem_usage_node(
+    _ctx: *mut raw::RedisModuleKeyOptCtx,
+    value: *const c_void,
+    _sample_size: usize,
+) -> usize {
+    let node = unsafe { &*value.cast::<NodeRedis>() };
+    std::mem::size_of::<NodeRedis>()
+        + node.data.capacity() * std::mem::size_of::<f32>()
+        + node.neighbors.capacity() * std::mem::size_of::<Vec<String>>()
+        + node
+            .neighbors
+            .iter()
+            .map(|l| {
+                l.capacity() * std::mem::size_of::<String>()
+                    + l.iter().map(|n| n.capacity()).sum::<usize>()
+            })
+            .sum::<usize>()
+}
+
 unsafe extern "C" fn free_node(value: *mut c_void) {
     drop(Box::from_raw(value as *mut NodeRedis));
 }
 
-unsafe extern "C" fn load_node(rdb: *mut raw::RedisModuleIO, version: i32) -> *mut c_void {
-    if version != NODE_VERSION {
-        return ptr::null_mut() as *mut c_void;
+unsafe extern "C" fn load_node(rdb: *mut raw::RedisModuleIO, encver: i32) -> *mut c_void {
+    if encver > NODE_VERSION {
+        logging::log_warning(format!(
+            "load hnsw node err: rdb encver {} is newer than module encver {}, upgrade the module",
+            encver, NODE_VERSION
+        ));
+        return ptr::null_mut();
     }
+    match load_node_encver(rdb) {
+        Ok(node) => Box::into_raw(Box::new(node)) as *mut c_void,
+        Err(e) => {
+            logging::log_warning(format!("load hnsw node (encver {}) err {}", encver, e));
+            ptr::null_mut()
+        }
+    }
+}
 
-    let mut node = Box::new(NodeRedis::default());
+// encver 0: node vector, neighbor node names of each layer
+unsafe fn load_node_encver(rdb: *mut raw::RedisModuleIO) -> RdbResult<NodeRedis> {
+    let mut node = NodeRedis::default();
 
-    let num_datum = raw::RedisModule_LoadUnsigned.unwrap()(rdb) as usize;
-    node.data = Vec::with_capacity(num_datum);
+    let num_datum = rdb::load_len(rdb)?;
+    node.data = rdb::with_capacity(num_datum);
     for _d in 0..num_datum {
-        let datum = raw::RedisModule_LoadFloat.unwrap()(rdb);
-        node.data.push(datum);
-    }
-
-    let num_layers = raw::RedisModule_LoadUnsigned.unwrap()(rdb) as usize;
-    node.neighbors = Vec::with_capacity(num_layers);
-    for l in 0..num_layers {
-        let num_nodes = raw::RedisModule_LoadUnsigned.unwrap()(rdb) as usize;
-        node.neighbors.push(Vec::new());
-        for _n in 0..num_nodes {
-            let node_name = raw::RedisModule_LoadString.unwrap()(rdb);
-            node.neighbors[l].push(
-                redis_module::RedisString::from_ptr(node_name)
-                    .unwrap()
-                    .to_owned(),
-            );
-        }
+        node.data.push(rdb::load_float(rdb)?);
+    }
+
+    let num_layers = rdb::load_len(rdb)?;
+    node.neighbors = rdb::with_capacity(num_layers);
+    for _l in 0..num_layers {
+        node.neighbors.push(load_names(rdb)?);
     }
 
-    let p: *mut c_void = Box::into_raw(node) as *mut c_void;
-    p
+    Ok(node)
 }
 
 unsafe extern "C" fn save_node(rdb: *mut raw::RedisModuleIO, value: *mut c_void) {
@@ -0,0 +1,68 @@
+use std::os::raw::c_int;
+
+use redis_module::{raw, Context, RedisError, RedisString};
+
+// current_user_name
+// None for fake clients, e.g. aof loading and the master link, they aren't restricted by acl.
+pub fn current_user_name(ctx: &Context) -> Option<String> {
+    unsafe {
+        let ctx_flags = raw::RedisModule_GetContextFlags.unwrap()(ctx.ctx);
+        if ctx_flags
+            & (raw::REDISMODULE_CTX_FLAGS_REPLICATED | raw::REDISMODULE_CTX_FLAGS_LOADING) as c_int
+            != 0
+        {
+            return None;
+        }
+        let user_name = raw::RedisModule_GetCurrentUserName.unwrap()(ctx.ctx);
+        if user_name.is_null() {
+            return None;
+        }
+        let name = RedisString::from_ptr(user_name).unwrap_or("").to_string();
+        raw::RedisModule_FreeString.unwrap()(ctx.ctx, user_name);
+        Some(name)
+    }
+}
+
+// check_key_acl
+// redis checks the acl of the cmd key args, keys which aren't cmd key args are checked explicitly,
+// e.g. the index which an alias is resolved to, and the listed indexes and aliases.
+pub fn check_key_acl(ctx: &Context, key_name: &RedisString, write: bool) -> Result<(), RedisError> {
+    match current_user_name(ctx) {
+        Some(user_name) => check_user_key_acl(ctx, &user_name, key_name, write),
+        None => Ok(()),
+    }
+}
+
+// check_user_key_acl
+// check the acl key permission of the user by name, e.g. the user of a dkann request from another shard
+pub fn check_user_key_acl(
+    ctx: &Context,
+    user_name: &str,
+    key_name: &RedisString,
+    write: bool,
+) -> Result<(), RedisError> {
+    let flags = if write {
+        raw::REDISMODULE_CMD_KEY_INSERT
+            | raw::REDISMODULE_CMD_KEY_DELETE
+            | raw::REDISMODULE_CMD_KEY_UPDATE
+    } else {
+        raw::REDISMODULE_CMD_KEY_ACCESS
+    };
+    let user_name = ctx.create_string(user_name);
+    unsafe {
+        let user = raw::RedisModule_GetModuleUserFromUserName.unwrap()(user_name.inner);
+        if user.is_null() {
+            return Err(RedisError::Str("NOPERM user does not exist"));
+        }
+        let res =
+            raw::RedisModule_ACLCheckKeyPermissions.unwrap()(user, key_name.inner, flags as c_int);
+        raw::RedisModule_FreeModuleUser.unwrap()(user);
+        if res != raw::REDISMODULE_OK as c_int {
+            return Err(RedisError::String(format!(
+                "NOPERM this user has no permissions to access the '{}' key",
+                key_name
+            )));
+        }
+    }
+    Ok(())
+}
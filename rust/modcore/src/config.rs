@@ -0,0 +1,94 @@
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+
+use redis_module::configuration::ConfigurationContext;
+use redis_module::{Context, RedisString};
+
+use crate::pool;
+
+pub static DEFAULT_SEARCH_THREADS: i64 = 4;
+pub static MAX_SEARCH_THREADS: i64 = 1024;
+
+lazy_static! {
+    // configs of every module: CONFIG SET/GET <module>.<name>, saved by CONFIG REWRITE.
+    // they are read on the main thread and workers, so they're atomics instead of gil guarded values.
+
+    // search worker threads, 0 runs searches on the main thread
+    pub static ref SEARCH_THREADS: AtomicI64 = AtomicI64::new(DEFAULT_SEARCH_THREADS);
+    // fire module keyspace events of the write cmds, e.g. hnsw.node.add
+    pub static ref NOTIFY_EVENTS: AtomicBool = AtomicBool::new(true);
+}
+
+pub fn search_threads() -> usize {
+    SEARCH_THREADS.load(Ordering::Relaxed) as usize
+}
+
+pub fn notify_events() -> bool {
+    NOTIFY_EVENTS.load(Ordering::Relaxed)
+}
+
+// CONFIG SET <module>.search_threads n resizes the worker pool
+pub fn on_search_threads_changed(
+    _config_ctx: &ConfigurationContext,
+    _name: &str,
+    threads: &'static AtomicI64,
+) {
+    pool::resize(threads.load(Ordering::Relaxed) as usize);
+}
+
+// apply_module_args
+// module args are key value pairs, e.g. --loadmodule libredisxann_hnsw.so search_threads 8,
+// they're applied as configs, so configs in redis.conf are overridden by them.
+// apply_module_arg applies the configs of the module, return false if the name isn't its config.
+pub fn apply_module_args(
+    ctx: &Context,
+    args: &[RedisString],
+    mut apply_module_arg: impl FnMut(&str, &str) -> Result<bool, String>,
+) -> Result<(), String> {
+    if args.len() % 2 != 0 {
+        return Err(format!(
+            "module arguments len {}, must be key:value pairs",
+            args.len()
+        ));
+    }
+
+    for pair in args.chunks(2) {
+        let name = pair[0].to_string_lossy().to_string();
+        let value = pair[1].to_string_lossy().to_string();
+        match name.to_lowercase().as_str() {
+            "search_threads" => {
+                let threads = parse_i64(&name, &value, 0, MAX_SEARCH_THREADS)?;
+                SEARCH_THREADS.store(threads, Ordering::Relaxed);
+            }
+            "notify_events" => {
+                NOTIFY_EVENTS.store(parse_bool(&name, &value)?, Ordering::Relaxed);
+            }
+            _ => {
+                if !apply_module_arg(&name, &value)? {
+                    ctx.log_warning(format!("unknown module argument {}", name).as_str());
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn parse_bool(name: &str, value: &str) -> Result<bool, String> {
+    match value.to_lowercase().as_str() {
+        "yes" | "true" | "1" => Ok(true),
+        "no" | "false" | "0" => Ok(false),
+        _ => Err(format!(
+            "module argument {} must be yes or no, got {}",
+            name, value
+        )),
+    }
+}
+
+pub fn parse_i64(name: &str, value: &str, min: i64, max: i64) -> Result<i64, String> {
+    match value.parse::<i64>() {
+        Ok(n) if (min..=max).contains(&n) => Ok(n),
+        _ => Err(format!(
+            "module argument {} must be in [{}, {}], got {}",
+            name, min, max, value
+        )),
+    }
+}
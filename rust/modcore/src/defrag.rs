@@ -0,0 +1,82 @@
+use std::os::raw::{c_ulong, c_void};
+use std::ptr;
+
+use redis_module::raw;
+
+// Defrag
+// activedefrag of the ann values, heap buffers allocated by the redis allocator are moved by
+// RedisModule_DefragAlloc, a big value is defragged in steps with a cursor.
+// note: callbacks run on the main thread, buffers shared with workers must be locked.
+pub struct Defrag(*mut raw::RedisModuleDefragCtx);
+
+impl Defrag {
+    pub fn new(ctx: *mut raw::RedisModuleDefragCtx) -> Self {
+        Defrag(ctx)
+    }
+
+    // the time of this defrag step is used up, save a cursor and continue in the next step
+    pub fn should_stop(&self) -> bool {
+        unsafe { raw::RedisModule_DefragShouldStop.unwrap()(self.0) != 0 }
+    }
+
+    // the cursor saved by the previous step, None for the first step
+    pub fn cursor(&self) -> Option<u64> {
+        let mut cursor: c_ulong = 0;
+        let res = unsafe { raw::RedisModule_DefragCursorGet.unwrap()(self.0, &mut cursor) };
+        match res == raw::REDISMODULE_OK as i32 {
+            true => Some(cursor as u64),
+            false => None,
+        }
+    }
+
+    pub fn set_cursor(&self, cursor: u64) {
+        unsafe {
+            raw::RedisModule_DefragCursorSet.unwrap()(self.0, cursor as c_ulong);
+        }
+    }
+
+    // move the allocation, null if it isn't moved, the old allocation is freed if it's moved
+    fn alloc(&self, ptr: *mut c_void) -> *mut c_void {
+        unsafe { raw::RedisModule_DefragAlloc.unwrap()(self.0, ptr) }
+    }
+
+    // move the boxed value of the key
+    pub fn value(&self, value: *mut *mut c_void) {
+        unsafe {
+            if !(*value).is_null() {
+                let moved = self.alloc(*value);
+                if !moved.is_null() {
+                    *value = moved;
+                }
+            }
+        }
+    }
+
+    // move the vec buffer, elements are moved bitwise
+    pub fn vec<T>(&self, v: &mut Vec<T>) {
+        if v.capacity() == 0 || std::mem::size_of::<T>() == 0 {
+            return;
+        }
+        let moved = self.alloc(v.as_mut_ptr().cast::<c_void>());
+        if !moved.is_null() {
+            // the old buffer is freed by redis, overwrite the vec without dropping it
+            unsafe {
+                let moved = Vec::from_raw_parts(moved.cast::<T>(), v.len(), v.capacity());
+                ptr::write(v, moved);
+            }
+        }
+    }
+
+    pub fn string(&self, s: &mut String) {
+        if s.capacity() == 0 {
+            return;
+        }
+        let moved = self.alloc(s.as_mut_ptr().cast::<c_void>());
+        if !moved.is_null() {
+            unsafe {
+                let moved = String::from_raw_parts(moved.cast::<u8>(), s.len(), s.capacity());
+                ptr::write(s, moved);
+            }
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use redis_module::raw;
+
+// Digest
+// DEBUG DIGEST / DEBUG DIGEST-VALUE of the ann values, values are added in a deterministic order,
+// so replicas and restored instances holding the same vectors have the same digest.
+pub struct Digest(*mut raw::RedisModuleDigest);
+
+impl Digest {
+    pub fn new(md: *mut raw::RedisModuleDigest) -> Self {
+        Digest(md)
+    }
+
+    pub fn add_bytes(&self, buf: &[u8]) {
+        unsafe {
+            raw::RedisModule_DigestAddStringBuffer.unwrap()(
+                self.0,
+                buf.as_ptr() as *mut _,
+                buf.len(),
+            );
+        }
+    }
+
+    pub fn add_str(&self, s: &str) {
+        self.add_bytes(s.as_bytes());
+    }
+
+    pub fn add_u64(&self, n: u64) {
+        unsafe {
+            raw::RedisModule_DigestAddLongLong.unwrap()(self.0, n as i64);
+        }
+    }
+
+    // end the sequence of an ordered element, e.g. a node with its vector
+    pub fn end_sequence(&self) {
+        unsafe {
+            raw::RedisModule_DigestEndSequence.unwrap()(self.0);
+        }
+    }
+}
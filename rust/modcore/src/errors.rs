@@ -0,0 +1,53 @@
+use std::fmt::Display;
+
+use redis_module::RedisError;
+
+// ErrorCode
+// the code is the first word of the error reply, e.g. "-NOINDEX Index: usearch.idx0 does not exist",
+// the ann modules share the codes, so clients can handle the errors by the code.
+pub enum ErrorCode {
+    // vector dimensions don't match the index dimensions
+    DimMismatch,
+    // index doesn't exist
+    NoIndex,
+    // node or vector id doesn't exist
+    NoNode,
+    // unknown param name, malformed param value or unpaired params
+    BadParam,
+    // index capacity can't be reserved or is full
+    Capacity,
+    // index is busy with searches, the write can be retried
+    TryAgain,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::DimMismatch => "DIMMISMATCH",
+            ErrorCode::NoIndex => "NOINDEX",
+            ErrorCode::NoNode => "NONODE",
+            ErrorCode::BadParam => "BADPARAM",
+            ErrorCode::Capacity => "CAPACITY",
+            ErrorCode::TryAgain => "TRYAGAIN",
+        }
+    }
+}
+
+pub fn error(code: ErrorCode, msg: impl Display) -> RedisError {
+    RedisError::String(format!("{} {}", code.as_str(), msg))
+}
+
+pub fn no_index(name: impl Display) -> RedisError {
+    error(
+        ErrorCode::NoIndex,
+        format!("Index: {} does not exist", name),
+    )
+}
+
+pub fn no_node(name: impl Display) -> RedisError {
+    error(ErrorCode::NoNode, format!("Node: {} does not exist", name))
+}
+
+pub fn bad_param(msg: impl Display) -> RedisError {
+    error(ErrorCode::BadParam, msg)
+}
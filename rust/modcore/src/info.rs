@@ -0,0 +1,88 @@
+use std::os::raw::c_int;
+use std::ptr;
+
+use redis_module::native_types::RedisType;
+use redis_module::{raw, Context, InfoContext};
+
+use crate::{registry, stats};
+
+// INFO estimates the node names by samples, it's scraped periodically
+pub static INFO_MEM_SAMPLES: usize = 1024;
+
+// add_info
+// INFO <module> / INFO everything: the <module>_redisxann section with index counts, vectors and memory,
+// op counters with latency percentiles, and the last save/load durations.
+// vectors and mem_usage measure a registered index value of redis_type, mem_usage by (db, index).
+// note: index fields are index_<db>_<encoded index key name>:vectors=n,mem_usage=n,
+// the mem usage of an index is measured again after 10s, at most 16 indexes by an INFO.
+pub fn add_info<T>(
+    ctx: &InfoContext,
+    for_crash_report: bool,
+    redis_type: &RedisType,
+    vectors: impl Fn(&T) -> usize,
+    mem_usage: impl Fn(i32, &T) -> usize,
+) {
+    // keys aren't opened for the crash report
+    let indexes = match for_crash_report {
+        true => Vec::new(),
+        false => index_infos(redis_type, vectors, mem_usage),
+    };
+
+    ctx.add_info_section(Some("redisxann"));
+    ctx.add_info_field_long_long("indexes", indexes.len() as i64);
+    ctx.add_info_field_long_long("vectors", indexes.iter().map(|i| i.2).sum::<usize>() as i64);
+    ctx.add_info_field_long_long(
+        "mem_usage",
+        indexes.iter().map(|i| i.3).sum::<usize>() as i64,
+    );
+    stats::add_info_fields(ctx);
+    for (db, name, vectors, mem_usage) in indexes {
+        ctx.add_info_field_str(
+            &stats::info_index_field(db, &name),
+            &format!("vectors={},mem_usage={}", vectors, mem_usage),
+        );
+    }
+}
+
+// index_infos
+// (db, index key name, vectors, mem usage) of the registered indexes, the mem usage is cached by stats,
+// INFO runs on the main thread which holds the GIL, so the thread safe context isn't locked.
+fn index_infos<T>(
+    redis_type: &RedisType,
+    vectors: impl Fn(&T) -> usize,
+    mem_usage: impl Fn(i32, &T) -> usize,
+) -> Vec<(i32, String, usize, usize)> {
+    let raw_ctx = unsafe { raw::RedisModule_GetThreadSafeContext.unwrap()(ptr::null_mut()) };
+    let ctx = Context::new(raw_ctx);
+    let with_index = |db: i32, name: &str, f: &dyn Fn(&T) -> usize| {
+        if unsafe { raw::RedisModule_SelectDb.unwrap()(raw_ctx, db) }
+            != raw::REDISMODULE_OK as c_int
+        {
+            return None;
+        }
+        let key = ctx.open_key(&ctx.create_string(name));
+        match key.get_value::<T>(redis_type) {
+            Ok(Some(index)) => Some(f(index)),
+            _ => None,
+        }
+    };
+
+    let mut keys = Vec::new();
+    let mut counts = Vec::new();
+    for (db, name) in registry::all() {
+        if let Some(n) = with_index(db, &name, &vectors) {
+            keys.push((db, name));
+            counts.push(n);
+        }
+    }
+    let mem_usages = stats::info_mem_usage(&keys, |db, name| {
+        with_index(db, name, &|index| mem_usage(db, index))
+    });
+    unsafe { raw::RedisModule_FreeThreadSafeContext.unwrap()(raw_ctx) };
+
+    keys.into_iter()
+        .zip(counts)
+        .zip(mem_usages)
+        .map(|(((db, name), vectors), mem_usage)| (db, name, vectors, mem_usage))
+        .collect()
+}
@@ -0,0 +1,21 @@
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
+// modcore
+// the parts shared by the redisxann modules: checked rdb loads, rdb digest and defrag helpers, error codes,
+// bounded index locks of the main thread, op stats for INFO and the search worker pool,
+// and the index registry, module configs, INFO section and key acl checks of the module cmds.
+// note: each module links its own copy, so the statics (index registry, configs, stats, worker pool) aren't shared between modules.
+#[macro_use]
+extern crate lazy_static;
+
+pub mod acl;
+pub mod config;
+pub mod defrag;
+pub mod digest;
+pub mod errors;
+pub mod info;
+pub mod lock;
+pub mod pool;
+pub mod rdb;
+pub mod registry;
+pub mod stats;
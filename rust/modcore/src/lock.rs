@@ -0,0 +1,63 @@
+use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError, TryLockResult};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use redis_module::{Context, ContextFlags, RedisError};
+
+use crate::errors::{self, ErrorCode};
+
+// the main thread waits for the index lock at most this long, then the cmd is replied TRYAGAIN
+static LOCK_TIMEOUT: Duration = Duration::from_millis(50);
+static LOCK_RETRY_INTERVAL: Duration = Duration::from_micros(100);
+
+// write_lock
+// lock the index changes on the main thread, try_write is retried until LOCK_TIMEOUT,
+// so a long search on a worker doesn't block the main thread, the client retries on TRYAGAIN.
+// note: replicated cmds and loading wait for the lock, replicas must apply the master writes.
+pub fn write_lock<'a, T>(
+    ctx: &Context,
+    name: &str,
+    lock: &'a RwLock<T>,
+) -> Result<RwLockWriteGuard<'a, T>, RedisError> {
+    if must_wait(ctx) {
+        return Ok(lock.write().unwrap_or_else(|e| e.into_inner()));
+    }
+    retry(name, || lock.try_write())
+}
+
+// read_lock
+// like write_lock, for the reads on the main thread which wait for a worker holding the write lock
+pub fn read_lock<'a, T>(
+    ctx: &Context,
+    name: &str,
+    lock: &'a RwLock<T>,
+) -> Result<RwLockReadGuard<'a, T>, RedisError> {
+    if must_wait(ctx) {
+        return Ok(lock.read().unwrap_or_else(|e| e.into_inner()));
+    }
+    retry(name, || lock.try_read())
+}
+
+fn must_wait(ctx: &Context) -> bool {
+    ctx.get_flags()
+        .intersects(ContextFlags::REPLICATED | ContextFlags::LOADING)
+}
+
+fn retry<G>(name: &str, try_lock: impl Fn() -> TryLockResult<G>) -> Result<G, RedisError> {
+    let start = Instant::now();
+    loop {
+        match try_lock() {
+            Ok(guard) => return Ok(guard),
+            Err(TryLockError::Poisoned(e)) => return Ok(e.into_inner()),
+            Err(TryLockError::WouldBlock) if start.elapsed() < LOCK_TIMEOUT => {
+                thread::sleep(LOCK_RETRY_INTERVAL)
+            }
+            Err(TryLockError::WouldBlock) => {
+                return Err(errors::error(
+                    ErrorCode::TryAgain,
+                    format!("Index: {} is busy with searches", name),
+                ))
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+use std::os::raw::c_int;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+
+use redis_module::{logging, raw, BlockedClient, Context, RedisError};
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+lazy_static! {
+    // search worker pool, None runs searches on the main thread
+    static ref POOL: RwLock<Option<WorkerPool>> = RwLock::new(None);
+    // worker thread name prefix of the module
+    static ref POOL_NAME: Mutex<String> = Mutex::new(String::new());
+}
+
+struct WorkerPool {
+    sender: Mutex<Sender<Job>>,
+}
+
+impl WorkerPool {
+    fn new(name: &str, threads: usize) -> Self {
+        let (sender, receiver) = channel::<Job>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        for i in 0..threads {
+            let receiver = receiver.clone();
+            thread::Builder::new()
+                .name(format!("{}-worker-{}", name, i))
+                .spawn(move || worker(receiver))
+                .unwrap();
+        }
+        WorkerPool {
+            sender: Mutex::new(sender),
+        }
+    }
+}
+
+// the worker keeps running after a job panics, so the pool doesn't lose its workers
+fn worker(receiver: Arc<Mutex<Receiver<Job>>>) {
+    loop {
+        let job = match receiver.lock().unwrap_or_else(|e| e.into_inner()).recv() {
+            Ok(job) => job,
+            Err(_) => return,
+        };
+        if panic::catch_unwind(AssertUnwindSafe(job)).is_err() {
+            logging::log_warning("search worker job panicked");
+        }
+    }
+}
+
+// init the worker pool with threads named <name>-worker-<i>, 0 threads disable the pool
+pub fn init(name: &str, threads: usize) {
+    *POOL_NAME.lock().unwrap() = name.to_string();
+    resize(threads);
+}
+
+// resize the worker pool, e.g. by CONFIG SET <module>.search_threads,
+// workers of the replaced pool exit after the searches queued on them.
+pub fn resize(threads: usize) {
+    let name = POOL_NAME.lock().unwrap().clone();
+    *POOL.write().unwrap() = match threads {
+        0 => None,
+        n => Some(WorkerPool::new(&name, n)),
+    };
+}
+
+// can_dispatch
+// the search can be dispatched to the worker pool if the pool is enabled and the client can be blocked,
+// run on the main thread otherwise.
+pub fn can_dispatch(ctx: &Context) -> bool {
+    POOL.read().unwrap().is_some() && can_block(ctx)
+}
+
+// can_block
+// the client can't be blocked in MULTI, lua scripts and fake clients (e.g. aof loading).
+pub fn can_block(ctx: &Context) -> bool {
+    let flags = unsafe { raw::RedisModule_GetContextFlags.unwrap()(ctx.ctx) };
+    can_block_flags(flags)
+}
+
+// can_block_flags
+// the context flags allow blocking the client, see can_block
+pub fn can_block_flags(flags: c_int) -> bool {
+    let deny = raw::REDISMODULE_CTX_FLAGS_MULTI
+        | raw::REDISMODULE_CTX_FLAGS_LUA
+        | raw::REDISMODULE_CTX_FLAGS_DENY_BLOCKING
+        | raw::REDISMODULE_CTX_FLAGS_REPLICATED
+        | raw::REDISMODULE_CTX_FLAGS_LOADING;
+    flags & deny as c_int == 0
+}
+
+// dispatch
+// block the client and run the job with the blocked client on a worker, the job replies by it.
+// note: the job can't run on the main thread, it locks the thread safe context which the main thread holds,
+// so it's an error if the pool is disabled, callers check can_dispatch and search on the main thread instead.
+pub fn dispatch(
+    ctx: &Context,
+    job: impl FnOnce(BlockedClient) + Send + 'static,
+) -> Result<(), RedisError> {
+    let pool = POOL.read().unwrap();
+    let pool = pool
+        .as_ref()
+        .ok_or(RedisError::Str("ERR search worker pool is disabled"))?;
+    let blocked_client = ctx.block_client();
+    let job: Job = Box::new(move || job(blocked_client));
+    // workers don't exit while the pool is alive, the send doesn't fail
+    pool.sender
+        .lock()
+        .unwrap()
+        .send(job)
+        .map_err(|_| RedisError::Str("ERR search worker pool is closed"))
+}
@@ -0,0 +1,59 @@
+use redis_module::raw;
+use serde::de::DeserializeOwned;
+
+// checked rdb loads, IO errors and corrupt data are returned as errors instead of panics.
+// the type load callbacks log the error and return null, then redis reports the rdb load error
+// (or RESTORE replies an error) instead of aborting.
+
+// counts in a corrupt rdb maybe huge, don't preallocate more than this for them
+static MAX_PREALLOC_LEN: usize = 1 << 16;
+
+static MAX_LEN: u64 = u32::MAX as u64;
+
+pub type RdbResult<T> = Result<T, String>;
+
+pub unsafe fn load_unsigned(rdb: *mut raw::RedisModuleIO) -> RdbResult<u64> {
+    raw::load_unsigned(rdb).map_err(|e| format!("load unsigned err {}", e))
+}
+
+// load a count of the following items, a count greater than MAX_LEN is corrupt data
+pub unsafe fn load_len(rdb: *mut raw::RedisModuleIO) -> RdbResult<usize> {
+    let len = load_unsigned(rdb)?;
+    if len > MAX_LEN {
+        return Err(format!("len {} > max {}", len, MAX_LEN));
+    }
+    Ok(len as usize)
+}
+
+pub unsafe fn load_double(rdb: *mut raw::RedisModuleIO) -> RdbResult<f64> {
+    raw::load_double(rdb).map_err(|e| format!("load double err {}", e))
+}
+
+pub unsafe fn load_float(rdb: *mut raw::RedisModuleIO) -> RdbResult<f32> {
+    raw::load_float(rdb).map_err(|e| format!("load float err {}", e))
+}
+
+pub unsafe fn load_string(rdb: *mut raw::RedisModuleIO) -> RdbResult<String> {
+    let s = raw::load_string(rdb).map_err(|e| format!("load string err {}", e))?;
+    s.try_as_str()
+        .map(|s| s.to_owned())
+        .map_err(|e| format!("load string err {}", e))
+}
+
+pub unsafe fn load_buffer(rdb: *mut raw::RedisModuleIO) -> RdbResult<Vec<u8>> {
+    raw::load_string_buffer(rdb)
+        .map(|buf| buf.as_ref().to_vec())
+        .map_err(|e| format!("load buffer err {}", e))
+}
+
+pub fn with_capacity<T>(len: usize) -> Vec<T> {
+    Vec::with_capacity(len.min(MAX_PREALLOC_LEN))
+}
+
+pub unsafe fn load_json<T: DeserializeOwned>(
+    rdb: *mut raw::RedisModuleIO,
+    what: &str,
+) -> RdbResult<T> {
+    let json = load_string(rdb)?;
+    serde_json::from_str(&json).map_err(|e| format!("load {} json err {}", what, e))
+}
@@ -0,0 +1,73 @@
+use std::collections::{BTreeSet, HashMap};
+use std::sync::RwLock;
+
+lazy_static! {
+    // index key names in each db, kept in sync by module cmds and keyspace/server events,
+    // so list index don't need to scan the whole keyspace.
+    static ref INDEX_REGISTRY: RwLock<HashMap<i32, BTreeSet<String>>> = RwLock::new(HashMap::new());
+}
+
+pub fn add(db: i32, name: &str) {
+    INDEX_REGISTRY
+        .write()
+        .unwrap()
+        .entry(db)
+        .or_default()
+        .insert(name.to_string());
+}
+
+pub fn remove(db: i32, name: &str) -> bool {
+    let mut registry = INDEX_REGISTRY.write().unwrap();
+    match registry.get_mut(&db) {
+        Some(names) => names.remove(name),
+        None => false,
+    }
+}
+
+pub fn contains(db: i32, name: &str) -> bool {
+    match INDEX_REGISTRY.read().unwrap().get(&db) {
+        Some(names) => names.contains(name),
+        None => false,
+    }
+}
+
+// sorted index key names in db
+pub fn list(db: i32) -> Vec<String> {
+    match INDEX_REGISTRY.read().unwrap().get(&db) {
+        Some(names) => names.iter().cloned().collect(),
+        None => Vec::new(),
+    }
+}
+
+// (db, index key name) of all dbs
+pub fn all() -> Vec<(i32, String)> {
+    let registry = INDEX_REGISTRY.read().unwrap();
+    let mut all = registry
+        .iter()
+        .flat_map(|(db, names)| names.iter().map(|name| (*db, name.clone())))
+        .collect::<Vec<(i32, String)>>();
+    all.sort_unstable();
+    all
+}
+
+// flush db index key names, db -1 flush all dbs
+pub fn flush(db: i32) {
+    let mut registry = INDEX_REGISTRY.write().unwrap();
+    if db == -1 {
+        registry.clear();
+    } else {
+        registry.remove(&db);
+    }
+}
+
+pub fn swap(db1: i32, db2: i32) {
+    let mut registry = INDEX_REGISTRY.write().unwrap();
+    let names1 = registry.remove(&db1);
+    let names2 = registry.remove(&db2);
+    if let Some(names) = names1 {
+        registry.insert(db2, names);
+    }
+    if let Some(names) = names2 {
+        registry.insert(db1, names);
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use redis_module::InfoContext;
+
+// latency histogram bucket i counts the calls which take [2^(i-1), 2^i) microseconds
+pub const LATENCY_BUCKETS: usize = 40;
+#[allow(clippy::declare_interior_mutable_const)]
+const ZERO: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Clone, Copy)]
+pub enum Op {
+    Insert,
+    Search,
+    Delete,
+}
+
+impl Op {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Op::Insert => "insert",
+            Op::Search => "search",
+            Op::Delete => "delete",
+        }
+    }
+
+    fn stats(&self) -> &'static OpStats {
+        match self {
+            Op::Insert => &INSERT_STATS,
+            Op::Search => &SEARCH_STATS,
+            Op::Delete => &DELETE_STATS,
+        }
+    }
+}
+
+// OpStats
+// calls and latency of the cmds which insert, search or delete vectors,
+// searches are recorded on workers, so they're atomics.
+struct OpStats {
+    calls: AtomicU64,
+    usec: AtomicU64,
+    buckets: [AtomicU64; LATENCY_BUCKETS],
+}
+
+impl OpStats {
+    const fn new() -> Self {
+        OpStats {
+            calls: AtomicU64::new(0),
+            usec: AtomicU64::new(0),
+            buckets: [ZERO; LATENCY_BUCKETS],
+        }
+    }
+
+    fn record(&self, usec: u64) {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        self.usec.fetch_add(usec, Ordering::Relaxed);
+        self.buckets[latency_bucket(usec)].fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn percentile_usec(&self, percentile: f64) -> u64 {
+        let counts = self
+            .buckets
+            .iter()
+            .map(|b| b.load(Ordering::Relaxed))
+            .collect::<Vec<u64>>();
+        percentile_usec(&counts, percentile)
+    }
+}
+
+// latency_bucket
+// the histogram bucket of a call which takes usec microseconds, the last bucket counts the longer calls.
+pub fn latency_bucket(usec: u64) -> usize {
+    let bucket = (u64::BITS - usec.leading_zeros()) as usize;
+    bucket.min(LATENCY_BUCKETS - 1)
+}
+
+// percentile_usec
+// return the upper bound of the bucket which the percentile of the bucket counts falls in, 0 if no calls
+pub fn percentile_usec(counts: &[u64], percentile: f64) -> u64 {
+    let total = counts.iter().sum::<u64>();
+    if total == 0 {
+        return 0;
+    }
+    let rank = (total as f64 * percentile).ceil() as u64;
+    let mut seen = 0;
+    for (i, count) in counts.iter().enumerate() {
+        seen += count;
+        if seen >= rank {
+            return 1 << i;
+        }
+    }
+    1 << (counts.len() - 1)
+}
+
+static INSERT_STATS: OpStats = OpStats::new();
+static SEARCH_STATS: OpStats = OpStats::new();
+static DELETE_STATS: OpStats = OpStats::new();
+
+// Timer
+// record the op latency when it's dropped, e.g. at the end of the cmd or the search on a worker
+pub struct Timer {
+    op: Op,
+    start: Instant,
+}
+
+impl Timer {
+    pub fn new(op: Op) -> Self {
+        Timer {
+            op,
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        self.op
+            .stats()
+            .record(self.start.elapsed().as_micros() as u64);
+    }
+}
+
+lazy_static! {
+    // start of the running rdb/aof save and loading
+    static ref SAVE_START: Mutex<Option<Instant>> = Mutex::new(None);
+    static ref LOAD_START: Mutex<Option<Instant>> = Mutex::new(None);
+    // (db, index key name) -> (measured at, mem usage) of the INFO index fields
+    static ref INFO_MEM_CACHE: Mutex<HashMap<(i32, String), (Instant, usize)>> = Mutex::new(HashMap::new());
+}
+static LAST_SAVE_USEC: AtomicU64 = AtomicU64::new(0);
+static LAST_LOAD_USEC: AtomicU64 = AtomicU64::new(0);
+
+pub fn save_started() {
+    *SAVE_START.lock().unwrap() = Some(Instant::now());
+}
+
+pub fn save_ended() {
+    if let Some(start) = SAVE_START.lock().unwrap().take() {
+        LAST_SAVE_USEC.store(start.elapsed().as_micros() as u64, Ordering::Relaxed);
+    }
+}
+
+pub fn load_started() {
+    *LOAD_START.lock().unwrap() = Some(Instant::now());
+}
+
+pub fn load_ended() {
+    if let Some(start) = LOAD_START.lock().unwrap().take() {
+        LAST_LOAD_USEC.store(start.elapsed().as_micros() as u64, Ordering::Relaxed);
+    }
+}
+
+// add the op counters, latency percentiles and last save/load durations to the info section
+pub fn add_info_fields(ctx: &InfoContext) {
+    for op in [Op::Insert, Op::Search, Op::Delete] {
+        let stats = op.stats();
+        let name = op.as_str();
+        let calls = stats.calls.load(Ordering::Relaxed);
+        ctx.add_info_field_long_long(&format!("{}_calls", name), calls as i64);
+        ctx.add_info_field_long_long(
+            &format!("{}_usec", name),
+            stats.usec.load(Ordering::Relaxed) as i64,
+        );
+        for (suffix, percentile) in [("p50", 0.5), ("p99", 0.99), ("p999", 0.999)] {
+            ctx.add_info_field_long_long(
+                &format!("{}_{}_usec", name, suffix),
+                stats.percentile_usec(percentile) as i64,
+            );
+        }
+    }
+    ctx.add_info_field_long_long(
+        "last_save_usec",
+        LAST_SAVE_USEC.load(Ordering::Relaxed) as i64,
+    );
+    ctx.add_info_field_long_long(
+        "last_load_usec",
+        LAST_LOAD_USEC.load(Ordering::Relaxed) as i64,
+    );
+}
+
+// INFO measures at most INFO_MEM_REFRESHES indexes which aren't measured in INFO_MEM_TTL
+static INFO_MEM_TTL: Duration = Duration::from_secs(10);
+static INFO_MEM_REFRESHES: usize = 16;
+
+// info_mem_usage
+// mem usage of the index keys for INFO, the stale indexes are measured the oldest first,
+// others use the cached mem usage, so an INFO doesn't measure every index.
+// return the mem usage of each key, 0 if it isn't measured yet
+pub fn info_mem_usage(
+    keys: &[(i32, String)],
+    measure: impl Fn(i32, &str) -> Option<usize>,
+) -> Vec<usize> {
+    let mut cache = INFO_MEM_CACHE.lock().unwrap();
+    let live = keys.iter().collect::<HashSet<&(i32, String)>>();
+    cache.retain(|key, _| live.contains(key));
+
+    let now = Instant::now();
+    let mut stale = keys
+        .iter()
+        .enumerate()
+        .filter_map(|(i, key)| match cache.get(key) {
+            Some((at, _)) if now.duration_since(*at) < INFO_MEM_TTL => None,
+            Some((at, _)) => Some((Some(*at), i)),
+            None => Some((None, i)),
+        })
+        .collect::<Vec<(Option<Instant>, usize)>>();
+    stale.sort_unstable();
+    for (_, i) in stale.into_iter().take(INFO_MEM_REFRESHES) {
+        let (db, name) = &keys[i];
+        if let Some(mem_usage) = measure(*db, name) {
+            cache.insert(keys[i].clone(), (now, mem_usage));
+        }
+    }
+
+    keys.iter()
+        .map(|key| cache.get(key).map_or(0, |(_, mem_usage)| *mem_usage))
+        .collect()
+}
+
+// info_index_field
+// index_<db>_<index key name> with the bytes of the name except [A-Za-z0-9._{}-] %XX encoded,
+// so the key name can't break the INFO field, e.g. ':' ',' or CRLF
+pub fn info_index_field(db: i32, name: &str) -> String {
+    let mut field = format!("index_{}_", db);
+    for b in name.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'.' | b'_' | b'{' | b'}' | b'-' => {
+                field.push(b as char)
+            }
+            _ => field.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    field
+}
@@ -0,0 +1,21 @@
+use modcore::pool::can_block_flags;
+use redis_module::raw;
+use std::os::raw::c_int;
+
+#[test]
+fn can_block_flags_test() {
+    assert!(can_block_flags(0));
+    assert!(can_block_flags(raw::REDISMODULE_CTX_FLAGS_MASTER as c_int));
+    for flag in [
+        raw::REDISMODULE_CTX_FLAGS_MULTI,
+        raw::REDISMODULE_CTX_FLAGS_LUA,
+        raw::REDISMODULE_CTX_FLAGS_DENY_BLOCKING,
+        raw::REDISMODULE_CTX_FLAGS_REPLICATED,
+        raw::REDISMODULE_CTX_FLAGS_LOADING,
+    ] {
+        assert!(!can_block_flags(flag as c_int));
+        assert!(!can_block_flags(
+            (flag | raw::REDISMODULE_CTX_FLAGS_MASTER) as c_int
+        ));
+    }
+}
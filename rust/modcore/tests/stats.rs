@@ -0,0 +1,70 @@
+use modcore::stats::*;
+use std::cell::Cell;
+
+#[test]
+fn latency_bucket_test() {
+    assert_eq!(latency_bucket(0), 0);
+    assert_eq!(latency_bucket(1), 1);
+    assert_eq!(latency_bucket(2), 2);
+    assert_eq!(latency_bucket(3), 2);
+    assert_eq!(latency_bucket(4), 3);
+    assert_eq!(latency_bucket(1023), 10);
+    assert_eq!(latency_bucket(1024), 11);
+    assert_eq!(latency_bucket(u64::MAX), LATENCY_BUCKETS - 1);
+}
+
+#[test]
+fn percentile_usec_test() {
+    let mut counts = vec![0; LATENCY_BUCKETS];
+    assert_eq!(percentile_usec(&counts, 0.5), 0);
+
+    // 90 calls in [512, 1024) usec, 9 in [4096, 8192) and 1 of the last bucket
+    counts[latency_bucket(600)] = 90;
+    counts[latency_bucket(5000)] = 9;
+    counts[latency_bucket(u64::MAX)] = 1;
+    assert_eq!(percentile_usec(&counts, 0.5), 1024);
+    assert_eq!(percentile_usec(&counts, 0.9), 1024);
+    assert_eq!(percentile_usec(&counts, 0.99), 8192);
+    assert_eq!(percentile_usec(&counts, 0.999), 1 << (LATENCY_BUCKETS - 1));
+    assert_eq!(percentile_usec(&counts, 1.0), 1 << (LATENCY_BUCKETS - 1));
+}
+
+#[test]
+fn info_index_field_test() {
+    assert_eq!(info_index_field(0, "hnsw.{idx0}"), "index_0_hnsw.{idx0}");
+    assert_eq!(
+        info_index_field(3, "usearch.a-b_c"),
+        "index_3_usearch.a-b_c"
+    );
+    assert_eq!(
+        info_index_field(1, "hnsw.{a:b,c}\r\n"),
+        "index_1_hnsw.{a%3Ab%2Cc}%0D%0A"
+    );
+    assert_eq!(info_index_field(0, "é"), "index_0_%C3%A9");
+}
+
+// note: the mem usage cache is global, it's the only test which calls info_mem_usage
+#[test]
+fn info_mem_usage_test() {
+    let keys = (0..20)
+        .map(|i| (0, format!("idx{}", i)))
+        .collect::<Vec<(i32, String)>>();
+    let measured = Cell::new(0);
+    let measure = |_db: i32, name: &str| {
+        measured.set(measured.get() + 1);
+        name[3..].parse::<usize>().ok()
+    };
+
+    // at most 16 indexes are measured per call, others are 0 until they're measured
+    let mem_usage = info_mem_usage(&keys, measure);
+    assert_eq!(measured.get(), 16);
+    assert_eq!(mem_usage.iter().filter(|m| **m == 0).count(), 5);
+
+    // the cached ones aren't measured again
+    let mem_usage = info_mem_usage(&keys, measure);
+    assert_eq!(measured.get(), 20);
+    assert_eq!(mem_usage, (0..20).collect::<Vec<usize>>());
+
+    let mem_usage = info_mem_usage(&keys[..2], |_, _| None);
+    assert_eq!(mem_usage, vec![0, 1]);
+}
@@ -1,6 +1,7 @@
 use anyhow::Context;
 use anyhow::Result;
-use redis::RedisError;
+use redis::{from_redis_value, RedisError, Value};
+use std::collections::HashMap;
 use utils::{get_redis_connection, start_redis_server_with_module};
 
 mod utils;
@@ -15,6 +16,18 @@ fn test_redisxann_hnsw() -> Result<()> {
     let mut con =
         get_redis_connection(port).with_context(|| "failed to connect to redis server")?;
 
+    // resize the search worker pool
+    let _: () = redis::cmd("CONFIG")
+        .arg(&["SET", "hnsw.search_threads", "2"])
+        .query(&mut con)
+        .with_context(|| "failed to run CONFIG SET")?;
+    let res: Vec<String> = redis::cmd("CONFIG")
+        .arg(&["GET", "hnsw.search_threads"])
+        .query(&mut con)
+        .with_context(|| "failed to run CONFIG GET")?;
+    assert_eq!(res, vec!["hnsw.search_threads", "2"]);
+
+    // the deprecated index name arg idx0 is the hash tagged index key hnsw.{idx0}
     let res: String = redis::cmd("hnsw.index.create")
         .arg(&["idx0", "dim", "3", "m", "10", "efcon", "12"])
         .query(&mut con)
@@ -27,5 +40,505 @@ fn test_redisxann_hnsw() -> Result<()> {
         return Err(anyhow::Error::msg("Should return an error"));
     }
 
+    for i in 0..3 {
+        let node_name = format!("n{}", i);
+        let res: String = redis::cmd("hnsw.node.add")
+            .arg(&["hnsw.{idx0}", node_name.as_str(), "0.1", "0.2", "0.3"])
+            .query(&mut con)
+            .with_context(|| "failed to run hnsw.node.add")?;
+        assert_eq!(res, "OK".to_string());
+    }
+
+    let res: (String, Vec<Vec<String>>) = redis::cmd("hnsw.node.scan")
+        .arg(&["hnsw.{idx0}", "0", "COUNT", "2"])
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.node.scan")?;
+    assert_eq!(res.1.len(), 2);
+    assert_eq!(
+        res.1[0],
+        vec!["name".to_string(), "hnsw.{idx0}.n0".to_string()]
+    );
+    assert_eq!(res.0, "hnsw.{idx0}.n2".to_string());
+
+    let res: (String, Vec<Vec<String>>) = redis::cmd("hnsw.node.scan")
+        .arg(&["hnsw.{idx0}", res.0.as_str(), "COUNT", "2"])
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.node.scan")?;
+    assert_eq!(res.1.len(), 1);
+    assert_eq!(res.0, "0".to_string());
+
+    let res: Vec<HashMap<String, Value>> = redis::cmd("hnsw.index.list")
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.index.list")?;
+    assert_eq!(res.len(), 1);
+    assert_eq!(
+        res[0].get("name").unwrap(),
+        &Value::Data("hnsw.{idx0}".into())
+    );
+    assert_eq!(res[0].get("node_count").unwrap(), &Value::Int(3));
+
+    // copied index has its own node keys
+    let res: usize = redis::cmd("COPY")
+        .arg(&["hnsw.{idx0}", "hnsw.{idx2}"])
+        .query(&mut con)
+        .with_context(|| "failed to run COPY")?;
+    assert_eq!(res, 1_usize);
+    let res: HashMap<String, Value> = redis::cmd("hnsw.node.get")
+        .arg(&["hnsw.{idx2}", "n1"])
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.node.get")?;
+    assert!(res.contains_key("data"));
+    let res: usize = redis::cmd("hnsw.index.del")
+        .arg(&["hnsw.{idx2}"])
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.index.del")?;
+    assert_eq!(res, 1_usize);
+    let res: HashMap<String, Value> = redis::cmd("hnsw.node.get")
+        .arg(&["hnsw.{idx0}", "n1"])
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.node.get")?;
+    assert!(res.contains_key("data"));
+
+    // COPY REPLACE removes the node keys of the replaced index
+    let res: String = redis::cmd("hnsw.index.create")
+        .arg(&["hnsw.{idx6}", "dim", "3", "m", "10", "efcon", "12"])
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.index.create")?;
+    assert_eq!(res, "OK".to_string());
+    let res: String = redis::cmd("hnsw.node.add")
+        .arg(&["hnsw.{idx6}", "old", "0.1", "0.2", "0.3"])
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.node.add")?;
+    assert_eq!(res, "OK".to_string());
+    let res: usize = redis::cmd("COPY")
+        .arg(&["hnsw.{idx0}", "hnsw.{idx6}", "REPLACE"])
+        .query(&mut con)
+        .with_context(|| "failed to run COPY")?;
+    assert_eq!(res, 1_usize);
+    let res: usize = redis::cmd("EXISTS")
+        .arg(&["hnsw.{idx6}.old", "hnsw.{idx6}.n1"])
+        .query(&mut con)
+        .with_context(|| "failed to run EXISTS")?;
+    assert_eq!(res, 1_usize);
+    let res: Result<HashMap<String, Value>, RedisError> = redis::cmd("hnsw.node.get")
+        .arg(&["hnsw.{idx6}", "old"])
+        .query(&mut con);
+    assert!(res.is_err());
+    let res: usize = redis::cmd("hnsw.index.del")
+        .arg(&["hnsw.{idx6}"])
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.index.del")?;
+    assert_eq!(res, 1_usize);
+
+    // COPY of an index without a node key doesn't leave the copied index
+    let res: String = redis::cmd("hnsw.index.create")
+        .arg(&["hnsw.{idx7}", "dim", "3", "m", "10", "efcon", "12"])
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.index.create")?;
+    assert_eq!(res, "OK".to_string());
+    for node_name in ["m0", "m1"] {
+        let res: String = redis::cmd("hnsw.node.add")
+            .arg(&["hnsw.{idx7}", node_name, "0.1", "0.2", "0.3"])
+            .query(&mut con)
+            .with_context(|| "failed to run hnsw.node.add")?;
+        assert_eq!(res, "OK".to_string());
+    }
+    let res: usize = redis::cmd("DEL")
+        .arg(&["hnsw.{idx7}.m1"])
+        .query(&mut con)
+        .with_context(|| "failed to run DEL")?;
+    assert_eq!(res, 1_usize);
+    let res: usize = redis::cmd("COPY")
+        .arg(&["hnsw.{idx7}", "hnsw.{idx8}"])
+        .query(&mut con)
+        .with_context(|| "failed to run COPY")?;
+    assert_eq!(res, 1_usize);
+    let res: usize = redis::cmd("EXISTS")
+        .arg(&["hnsw.{idx8}", "hnsw.{idx8}.m0", "hnsw.{idx8}.m1"])
+        .query(&mut con)
+        .with_context(|| "failed to run EXISTS")?;
+    assert_eq!(res, 0_usize);
+    let _: usize = redis::cmd("DEL")
+        .arg(&["hnsw.{idx7}", "hnsw.{idx7}.m0"])
+        .query(&mut con)
+        .with_context(|| "failed to run DEL")?;
+
+    // registry follows keyspace changes made by redis cmds
+    let _: () = redis::cmd("RENAME")
+        .arg(&["hnsw.{idx0}", "hnsw.{idx1}"])
+        .query(&mut con)
+        .with_context(|| "failed to run RENAME")?;
+    let res: Vec<HashMap<String, Value>> = redis::cmd("hnsw.index.list")
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.index.list")?;
+    assert_eq!(res.len(), 1);
+    assert_eq!(
+        res[0].get("name").unwrap(),
+        &Value::Data("hnsw.{idx1}".into())
+    );
+
+    // node keys follow the renamed index key
+    let res: HashMap<String, Value> = redis::cmd("hnsw.node.get")
+        .arg(&["hnsw.{idx1}", "n1"])
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.node.get")?;
+    let neighbors: Vec<Vec<String>> = from_redis_value(res.get("neighbors").unwrap())?;
+    assert!(!neighbors.is_empty());
+    for layer in neighbors.iter() {
+        for neighbor in layer.iter() {
+            assert!(neighbor.starts_with("hnsw.{idx1}."));
+        }
+    }
+    // the renamed values are renamed after RENAME, only the replicated rename can be applied
+    let res: Result<String, RedisError> = redis::cmd("hnsw.key.rename")
+        .arg(&["hnsw.{idx1}", "hnsw.{idx0}"])
+        .query(&mut con);
+    assert!(res.is_err());
+    let res: Vec<Value> = redis::cmd("hnsw.search.kann")
+        .arg(&["hnsw.{idx1}", "3", "0.1", "0.2", "0.3"])
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.search.kann")?;
+    assert_eq!(res[0], Value::Int(3));
+    let res: HashMap<String, Value> = redis::cmd("hnsw.search.profile")
+        .arg(&["hnsw.{idx1}", "3", "0.1", "0.2", "0.3"])
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.search.profile")?;
+    let results: Vec<Value> = from_redis_value(res.get("results").unwrap())?;
+    assert_eq!(results[0], Value::Int(3));
+    let profile: HashMap<String, Value> = from_redis_value(res.get("profile").unwrap())?;
+    let layers: Vec<HashMap<String, Value>> = from_redis_value(profile.get("layers").unwrap())?;
+    assert!(!layers.is_empty());
+    // greedy path from the top layer down to layer 0
+    assert_eq!(
+        from_redis_value::<usize>(layers.last().unwrap().get("layer").unwrap())?,
+        0
+    );
+    for pair in layers.windows(2) {
+        assert_eq!(pair[0].get("nearest"), pair[1].get("enterpoint"));
+    }
+    assert!(from_redis_value::<usize>(profile.get("visited").unwrap())? >= 3);
+    assert!(from_redis_value::<usize>(profile.get("distances").unwrap())? >= 3);
+    let res: Result<HashMap<String, Value>, RedisError> = redis::cmd("hnsw.node.get")
+        .arg(&["hnsw.{idx0}", "n1"])
+        .query(&mut con);
+    if res.is_ok() {
+        return Err(anyhow::Error::msg("Should return an error"));
+    }
+
+    // semantic errors reply with error codes instead of panics or wrong arity
+    for (args, code) in [
+        (vec!["hnsw.{idx1}", "3", "0.1", "0.2"], "DIMMISMATCH"),
+        (vec!["hnsw.{idx1}", "3", "0.1", "0.2", "x"], "BADPARAM"),
+        (vec!["hnsw.{idx_none}", "3", "0.1", "0.2", "0.3"], "NOINDEX"),
+        (vec!["hnsw.{idx1", "3", "0.1", "0.2", "0.3"], "BADPARAM"),
+    ] {
+        let res: Result<Vec<Value>, RedisError> =
+            redis::cmd("hnsw.search.kann").arg(&args).query(&mut con);
+        let err = res
+            .err()
+            .ok_or_else(|| anyhow::Error::msg("Should return an error"))?;
+        assert_eq!(err.code(), Some(code));
+    }
+    let res: Result<String, RedisError> = redis::cmd("hnsw.node.add")
+        .arg(&["hnsw.{idx1}", "n9", "0.1", "x", "0.3"])
+        .query(&mut con);
+    let err = res
+        .err()
+        .ok_or_else(|| anyhow::Error::msg("Should return an error"))?;
+    assert_eq!(err.code(), Some("BADPARAM"));
+    let res: Result<usize, RedisError> = redis::cmd("hnsw.node.del")
+        .arg(&["hnsw.{idx1}", "n_none"])
+        .query(&mut con);
+    let err = res
+        .err()
+        .ok_or_else(|| anyhow::Error::msg("Should return an error"))?;
+    assert_eq!(err.code(), Some("NONODE"));
+
+    let _: () = redis::cmd("FLUSHDB")
+        .query(&mut con)
+        .with_context(|| "failed to run FLUSHDB")?;
+    let res: Vec<Value> = redis::cmd("hnsw.index.list")
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.index.list")?;
+    assert_eq!(res.len(), 0);
+    // cached graph is dropped with the flushed keys
+    let res: Result<Vec<Value>, RedisError> = redis::cmd("hnsw.search.kann")
+        .arg(&["hnsw.{idx1}", "3", "0.1", "0.2", "0.3"])
+        .query(&mut con);
+    if res.is_ok() {
+        return Err(anyhow::Error::msg("Should return an error"));
+    }
+
+    // same index name in different dbs
+    for (db, dim) in [("0", "3"), ("1", "2")] {
+        let _: () = redis::cmd("SELECT")
+            .arg(db)
+            .query(&mut con)
+            .with_context(|| "failed to run SELECT")?;
+        let res: String = redis::cmd("hnsw.index.create")
+            .arg(&["hnsw.{idx3}", "dim", dim, "m", "10", "efcon", "12"])
+            .query(&mut con)
+            .with_context(|| "failed to run hnsw.index.create")?;
+        assert_eq!(res, "OK".to_string());
+    }
+    let res: HashMap<String, Value> = redis::cmd("hnsw.index.get")
+        .arg(&["hnsw.{idx3}"])
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.index.get")?;
+    assert_eq!(res.get("data_dim").unwrap(), &Value::Int(2));
+
+    // plain DEL drops the cached graph
+    let _: () = redis::cmd("DEL")
+        .arg(&["hnsw.{idx3}"])
+        .query(&mut con)
+        .with_context(|| "failed to run DEL")?;
+    let res: Result<HashMap<String, Value>, RedisError> = redis::cmd("hnsw.index.get")
+        .arg(&["hnsw.{idx3}"])
+        .query(&mut con);
+    if res.is_ok() {
+        return Err(anyhow::Error::msg("Should return an error"));
+    }
+    let _: () = redis::cmd("SELECT")
+        .arg("0")
+        .query(&mut con)
+        .with_context(|| "failed to run SELECT")?;
+    let res: HashMap<String, Value> = redis::cmd("hnsw.index.get")
+        .arg(&["hnsw.{idx3}"])
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.index.get")?;
+    assert_eq!(res.get("data_dim").unwrap(), &Value::Int(3));
+
+    // MOVE moves the node keys, an existing node key in the target db moves the index key back
+    for node_name in ["z0", "z1"] {
+        let res: String = redis::cmd("hnsw.node.add")
+            .arg(&["hnsw.{idx3}", node_name, "0.1", "0.2", "0.3"])
+            .query(&mut con)
+            .with_context(|| "failed to run hnsw.node.add")?;
+        assert_eq!(res, "OK".to_string());
+    }
+    let _: () = redis::cmd("SELECT")
+        .arg("1")
+        .query(&mut con)
+        .with_context(|| "failed to run SELECT")?;
+    let _: () = redis::cmd("SET")
+        .arg(&["hnsw.{idx3}.z1", "v"])
+        .query(&mut con)
+        .with_context(|| "failed to run SET")?;
+    let _: () = redis::cmd("SELECT")
+        .arg("0")
+        .query(&mut con)
+        .with_context(|| "failed to run SELECT")?;
+    let res: usize = redis::cmd("MOVE")
+        .arg(&["hnsw.{idx3}", "1"])
+        .query(&mut con)
+        .with_context(|| "failed to run MOVE")?;
+    assert_eq!(res, 1_usize);
+    let res: usize = redis::cmd("EXISTS")
+        .arg(&["hnsw.{idx3}", "hnsw.{idx3}.z0", "hnsw.{idx3}.z1"])
+        .query(&mut con)
+        .with_context(|| "failed to run EXISTS")?;
+    assert_eq!(res, 3_usize);
+    let _: () = redis::cmd("SELECT")
+        .arg("1")
+        .query(&mut con)
+        .with_context(|| "failed to run SELECT")?;
+    let _: () = redis::cmd("DEL")
+        .arg(&["hnsw.{idx3}.z1"])
+        .query(&mut con)
+        .with_context(|| "failed to run DEL")?;
+    let _: () = redis::cmd("SELECT")
+        .arg("0")
+        .query(&mut con)
+        .with_context(|| "failed to run SELECT")?;
+    let res: usize = redis::cmd("MOVE")
+        .arg(&["hnsw.{idx3}", "1"])
+        .query(&mut con)
+        .with_context(|| "failed to run MOVE")?;
+    assert_eq!(res, 1_usize);
+    let res: usize = redis::cmd("EXISTS")
+        .arg(&["hnsw.{idx3}", "hnsw.{idx3}.z0", "hnsw.{idx3}.z1"])
+        .query(&mut con)
+        .with_context(|| "failed to run EXISTS")?;
+    assert_eq!(res, 0_usize);
+    let _: () = redis::cmd("SELECT")
+        .arg("1")
+        .query(&mut con)
+        .with_context(|| "failed to run SELECT")?;
+    let res: Vec<Value> = redis::cmd("hnsw.search.kann")
+        .arg(&["hnsw.{idx3}", "2", "0.1", "0.2", "0.3"])
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.search.kann")?;
+    assert_eq!(res[0], Value::Int(2));
+    let _: () = redis::cmd("DEL")
+        .arg(&["hnsw.{idx3}", "hnsw.{idx3}.z0", "hnsw.{idx3}.z1"])
+        .query(&mut con)
+        .with_context(|| "failed to run DEL")?;
+    let _: () = redis::cmd("SELECT")
+        .arg("0")
+        .query(&mut con)
+        .with_context(|| "failed to run SELECT")?;
+
+    // compact storage, the whole graph lives in the index key
+    let res: String = redis::cmd("hnsw.index.create")
+        .arg(&[
+            "hnsw.{idx4}",
+            "dim",
+            "3",
+            "m",
+            "10",
+            "efcon",
+            "12",
+            "storage",
+            "compact",
+        ])
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.index.create")?;
+    assert_eq!(res, "OK".to_string());
+    for i in 0..3 {
+        let node_name = format!("n{}", i);
+        let res: String = redis::cmd("hnsw.node.add")
+            .arg(&["hnsw.{idx4}", node_name.as_str(), "0.1", "0.2", "0.3"])
+            .query(&mut con)
+            .with_context(|| "failed to run hnsw.node.add")?;
+        assert_eq!(res, "OK".to_string());
+    }
+    let res: i64 = redis::cmd("EXISTS")
+        .arg(&["hnsw.{idx4}.n1"])
+        .query(&mut con)
+        .with_context(|| "failed to run EXISTS")?;
+    assert_eq!(res, 0);
+    let res: HashMap<String, Value> = redis::cmd("hnsw.node.get")
+        .arg(&["hnsw.{idx4}", "n1"])
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.node.get")?;
+    assert!(res.contains_key("data"));
+    let res: (String, Vec<Vec<String>>) = redis::cmd("hnsw.node.scan")
+        .arg(&["hnsw.{idx4}", "0", "COUNT", "2"])
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.node.scan")?;
+    assert_eq!(res.0, "hnsw.{idx4}.n2".to_string());
+    assert_eq!(res.1.len(), 2);
+
+    // rdb save/load round trip, the restored value has the same digest
+    let digest: Vec<String> = redis::cmd("DEBUG")
+        .arg(&["DIGEST-VALUE", "hnsw.{idx4}"])
+        .query(&mut con)
+        .with_context(|| "failed to run DEBUG DIGEST-VALUE")?;
+    assert_ne!(digest[0], "0".repeat(40));
+    let dump: Vec<u8> = redis::cmd("DUMP")
+        .arg(&["hnsw.{idx4}"])
+        .query(&mut con)
+        .with_context(|| "failed to run DUMP")?;
+    let _: () = redis::cmd("RESTORE")
+        .arg("hnsw.{idx4}")
+        .arg(0)
+        .arg(dump)
+        .arg("REPLACE")
+        .query(&mut con)
+        .with_context(|| "failed to run RESTORE")?;
+    let res: HashMap<String, Value> = redis::cmd("hnsw.index.get")
+        .arg(&["hnsw.{idx4}"])
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.index.get")?;
+    assert_eq!(res.get("storage").unwrap(), &Value::Data("compact".into()));
+    assert_eq!(res.get("node_count").unwrap(), &Value::Int(3));
+    for field in ["graph_mem_usage", "vectors_mem_usage", "names_mem_usage"] {
+        assert_ne!(from_redis_value::<usize>(res.get(field).unwrap())?, 0);
+    }
+    let mem_usage = from_redis_value::<usize>(res.get("mem_usage").unwrap())?;
+    let res: usize = redis::cmd("MEMORY")
+        .arg(&["USAGE", "hnsw.{idx4}"])
+        .query(&mut con)
+        .with_context(|| "failed to run MEMORY USAGE")?;
+    assert!(res >= mem_usage);
+    let res: Vec<String> = redis::cmd("DEBUG")
+        .arg(&["DIGEST-VALUE", "hnsw.{idx4}"])
+        .query(&mut con)
+        .with_context(|| "failed to run DEBUG DIGEST-VALUE")?;
+    assert_eq!(res, digest);
+    let res: Vec<Value> = redis::cmd("hnsw.search.kann")
+        .arg(&["hnsw.{idx4}", "3", "0.1", "0.2", "0.3"])
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.search.kann")?;
+    assert_eq!(res[0], Value::Int(3));
+
+    let res: usize = redis::cmd("hnsw.index.del")
+        .arg(&["hnsw.{idx4}"])
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.index.del")?;
+    assert_eq!(res, 1);
+
+    // migrate legacy key layout hnsw.idx5, hnsw.idx5.* to hnsw.{idx5}, hnsw.{idx5}.*
+    let res: String = redis::cmd("hnsw.index.create")
+        .arg(&["hnsw.{idx5}", "dim", "3", "m", "10", "efcon", "12"])
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.index.create")?;
+    assert_eq!(res, "OK".to_string());
+    for i in 0..2 {
+        let node_name = format!("n{}", i);
+        let res: String = redis::cmd("hnsw.node.add")
+            .arg(&["hnsw.{idx5}", node_name.as_str(), "0.1", "0.2", "0.3"])
+            .query(&mut con)
+            .with_context(|| "failed to run hnsw.node.add")?;
+        assert_eq!(res, "OK".to_string());
+    }
+    let _: () = redis::cmd("RENAME")
+        .arg(&["hnsw.{idx5}", "hnsw.idx5"])
+        .query(&mut con)
+        .with_context(|| "failed to run RENAME")?;
+    let res: i64 = redis::cmd("EXISTS")
+        .arg(&["hnsw.idx5.n0", "hnsw.idx5.n1"])
+        .query(&mut con)
+        .with_context(|| "failed to run EXISTS")?;
+    assert_eq!(res, 2);
+    // legacy index args are accepted until the index is migrated, new legacy index keys aren't
+    for index_name in ["hnsw.idx5", "idx5"] {
+        let res: Vec<Value> = redis::cmd("hnsw.search.kann")
+            .arg(&[index_name, "2", "0.1", "0.2", "0.3"])
+            .query(&mut con)
+            .with_context(|| "failed to run hnsw.search.kann")?;
+        assert_eq!(res[0], Value::Int(2));
+    }
+    let res: Result<String, RedisError> = redis::cmd("hnsw.index.create")
+        .arg(&["hnsw.idx6", "dim", "3", "m", "10", "efcon", "12"])
+        .query(&mut con);
+    let err = res
+        .err()
+        .ok_or_else(|| anyhow::Error::msg("Should return an error"))?;
+    assert_eq!(err.code(), Some("BADPARAM"));
+    let res: usize = redis::cmd("hnsw.index.migrate")
+        .arg(&["hnsw.idx5", "hnsw.{idx5}"])
+        .query(&mut con)
+        .with_context(|| "failed to run hnsw.index.migrate")?;
+    assert_eq!(res, 2);
+    let res: i64 = redis::cmd("EXISTS")
+        .arg(&["hnsw.idx5", "hnsw.idx5.n0", "hnsw.idx5.n1"])
+        .query(&mut con)
+        .with_context(|| "failed to run EXISTS")?;
+    assert_eq!(res, 0);
+    for index_name in ["hnsw.{idx5}", "idx5"] {
+        let res: Vec<Value> = redis::cmd("hnsw.search.kann")
+            .arg(&[index_name, "2", "0.1", "0.2", "0.3"])
+            .query(&mut con)
+            .with_context(|| "failed to run hnsw.search.kann")?;
+        assert_eq!(res[0], Value::Int(2));
+    }
+
+    // test module info section, idx5 has 2 vectors
+    let res: String = redis::cmd("INFO")
+        .arg(&["hnsw"])
+        .query(&mut con)
+        .with_context(|| "failed to run INFO")?;
+    assert!(res.contains("# hnsw_redisxann"));
+    assert!(res.contains("index_0_hnsw.{idx5}:vectors=2,"));
+    for field in [
+        "indexes:",
+        "insert_calls:",
+        "search_p50_usec:",
+        "delete_calls:",
+    ] {
+        assert!(res.contains(field), "INFO misses {}", field);
+    }
+
     Ok(())
 }
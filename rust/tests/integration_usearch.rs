@@ -78,16 +78,49 @@ fn test_redisxann_usearch() -> Result<()> {
         vec![
             "serialization_file_path_dir",
             curr_dir.as_str(),
-            "is_remove_serialized_file",
-            "ok",
+            "remove_serialized_file",
+            "yes",
         ],
     )
     .with_context(|| "failed to start redis server")?];
     let mut con =
         get_redis_connection(port).with_context(|| "failed to connect to redis server")?;
 
+    // test module configs
+    let res: Vec<String> = redis::cmd("CONFIG")
+        .arg(&["GET", "usearch.remove_serialized_file"])
+        .query(&mut con)
+        .with_context(|| "failed to run CONFIG GET")?;
+    assert_eq!(res, vec!["usearch.remove_serialized_file", "yes"]);
+    let res: Vec<String> = redis::cmd("CONFIG")
+        .arg(&["GET", "usearch.ef_search"])
+        .query(&mut con)
+        .with_context(|| "failed to run CONFIG GET")?;
+    assert_eq!(res, vec!["usearch.ef_search", "3"]);
+    let _: () = redis::cmd("CONFIG")
+        .arg(&[
+            "SET",
+            "usearch.reserve_cap",
+            "100",
+            "usearch.search_threads",
+            "2",
+        ])
+        .query(&mut con)
+        .with_context(|| "failed to run CONFIG SET")?;
+    let res: Vec<String> = redis::cmd("CONFIG")
+        .arg(&["GET", "usearch.reserve_cap"])
+        .query(&mut con)
+        .with_context(|| "failed to run CONFIG GET")?;
+    assert_eq!(res, vec!["usearch.reserve_cap", "100"]);
+    let res: Result<(), RedisError> = redis::cmd("CONFIG")
+        .arg(&["SET", "usearch.reserve_cap", "0"])
+        .query(&mut con);
+    if res.is_ok() {
+        return Err(anyhow::Error::msg("Should return an error"));
+    }
+
     // test create index
-    let test_index_name = "test_idx0";
+    let test_index_name = "usearch.test_idx0";
     let res: String = redis::cmd("usearch.index.create")
         .arg(&[
             test_index_name,
@@ -114,8 +147,8 @@ fn test_redisxann_usearch() -> Result<()> {
     }
 
     // test get index
-    let eq_name = format!("usearch.{}", test_index_name);
-    let eq_path = format!("{}/{}.{}.idx", curr_dir, select_db, eq_name);
+    let eq_name = test_index_name.to_string();
+    let eq_path = format!("{}/usearch/{}.{}.idx", curr_dir, select_db, eq_name);
     let res: HashMap<String, Value> = redis::cmd("usearch.index.get")
         .arg(&[test_index_name])
         .query(&mut con)
@@ -143,6 +176,23 @@ fn test_redisxann_usearch() -> Result<()> {
         from_redis_value::<usize>(res.get("index_mem_usage").unwrap()).unwrap(),
         0
     );
+    let mem_usage = from_redis_value::<usize>(res.get("mem_usage").unwrap()).unwrap();
+    let res: usize = redis::cmd("MEMORY")
+        .arg(&["USAGE", eq_name.as_str()])
+        .query(&mut con)
+        .with_context(|| "failed to run MEMORY USAGE")?;
+    assert!(res >= mem_usage);
+
+    // test list index
+    let res: Vec<HashMap<String, Value>> = redis::cmd("usearch.index.list")
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.list")?;
+    assert_eq!(res.len(), 1);
+    assert_eq!(
+        res[0].get("name").unwrap(),
+        &Value::Data(eq_name.clone().into())
+    );
+    assert_eq!(res[0].get("dimensions").unwrap(), &Value::Int(3.into()));
 
     // test add index node
     let test_node_name = "n1";
@@ -168,7 +218,7 @@ fn test_redisxann_usearch() -> Result<()> {
         .query(&mut con)
         .with_context(|| format!("{}:{} failed to run usearch.node.get", file!(), line!()))?;
     println!("{res:?}");
-    let eq_node_name = format!("usearch.{}.{}", test_index_name, test_node_name);
+    let eq_node_name = format!("{}.{}", test_index_name, test_node_name);
     assert_eq!(res.get("name").unwrap(), &Value::Data(eq_node_name.into()));
     assert_eq!(
         res.get("data").unwrap(),
@@ -225,6 +275,206 @@ fn test_redisxann_usearch() -> Result<()> {
         from_redis_value::<usize>(res.get("index_size").unwrap()).unwrap(),
         n
     );
+    // test search profile
+    let res: HashMap<String, Value> = redis::cmd("usearch.search.profile")
+        .arg(&[test_index_name, "10", q_vector.as_str(), "ef_search", "16"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.search.profile")?;
+    let results: Vec<Value> = from_redis_value(res.get("results").unwrap())?;
+    assert_eq!(results[0], Value::Int(10));
+    let profile: HashMap<String, Value> = from_redis_value(res.get("profile").unwrap())?;
+    assert_eq!(
+        from_redis_value::<usize>(profile.get("ef_search").unwrap())?,
+        16
+    );
+    // the query ef_search doesn't change the index expansion
+    let res: HashMap<String, Value> = redis::cmd("usearch.search.profile")
+        .arg(&[test_index_name, "10", q_vector.as_str()])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.search.profile")?;
+    let profile: HashMap<String, Value> = from_redis_value(res.get("profile").unwrap())?;
+    assert_ne!(
+        from_redis_value::<usize>(profile.get("ef_search").unwrap())?,
+        16
+    );
+    let partitions: Vec<HashMap<String, Value>> =
+        from_redis_value(profile.get("partitions").unwrap())?;
+    assert_eq!(partitions.len(), 1);
+    assert_eq!(
+        from_redis_value::<usize>(partitions[0].get("size").unwrap())?,
+        n
+    );
+    // test scan index nodes
+    let mut cursor = "0".to_string();
+    let mut scanned = 0;
+    loop {
+        let res: (String, Vec<HashMap<String, Value>>) = redis::cmd("usearch.node.scan")
+            .arg(&[
+                test_index_name,
+                cursor.as_str(),
+                "COUNT",
+                "30",
+                "WITHVECTORS",
+            ])
+            .query(&mut con)
+            .with_context(|| format!("{}:{} failed to run usearch.node.scan", file!(), line!()))?;
+        for node in res.1.iter() {
+            assert!(from_redis_value::<usize>(node.get("id").unwrap()).unwrap() > 0);
+            assert!(node.contains_key("name"));
+            assert_eq!(
+                from_redis_value::<Vec<f64>>(node.get("data").unwrap()).unwrap(),
+                vec![1.0; 3]
+            );
+        }
+        scanned += res.1.len();
+        cursor = res.0;
+        if cursor == "0" {
+            break;
+        }
+    }
+    assert_eq!(scanned, n);
+
+    // test vectors added by id without node name are scanned after DEBUG RELOAD
+    let add_id_index_name = "usearch.add_id_idx";
+    let res: String = redis::cmd("usearch.index.create")
+        .arg(&[add_id_index_name, "dim", "3"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.create")?;
+    assert_eq!(res, "OK");
+    let res: String = redis::cmd("usearch.node.add")
+        .arg(&[add_id_index_name, "n1", "1.0,1.0,1.0", "id", "5"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.node.add")?;
+    assert_eq!(res, "OK");
+    for id in ["7", "3"] {
+        let res: String = redis::cmd("usearch.node.add_id")
+            .arg(&[add_id_index_name, id, "2.0,2.0,2.0"])
+            .query(&mut con)
+            .with_context(|| "failed to run usearch.node.add_id")?;
+        assert_eq!(res, "OK");
+    }
+    let _: () = redis::cmd("DEBUG")
+        .arg("RELOAD")
+        .query(&mut con)
+        .with_context(|| "failed to run DEBUG RELOAD")?;
+    let res: (String, Vec<HashMap<String, Value>>) = redis::cmd("usearch.node.scan")
+        .arg(&[add_id_index_name, "0", "COUNT", "10"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.node.scan")?;
+    assert_eq!(res.0, "0");
+    let nodes: Vec<(usize, String)> = res
+        .1
+        .iter()
+        .map(|node| {
+            (
+                from_redis_value(node.get("id").unwrap()).unwrap(),
+                from_redis_value(node.get("name").unwrap()).unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        nodes,
+        vec![
+            (3, "".to_string()),
+            (5, format!("{}.n1", add_id_index_name)),
+            (7, "".to_string()),
+        ]
+    );
+    let res: usize = redis::cmd("usearch.node.del_id")
+        .arg(&[add_id_index_name, "7"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.node.del_id")?;
+    assert_eq!(res, 1);
+    let res: (String, Vec<HashMap<String, Value>>) = redis::cmd("usearch.node.scan")
+        .arg(&[add_id_index_name, "0"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.node.scan")?;
+    assert_eq!(res.1.len(), 2);
+    let res: usize = redis::cmd("usearch.index.del")
+        .arg(&[add_id_index_name])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.del")?;
+    assert_eq!(res, 1);
+
+    // test copy index, the copied index is independent of the source index
+    let copy_index_name = "usearch.test_idx1";
+    let copy_key_name = copy_index_name.to_string();
+    let res: usize = redis::cmd("COPY")
+        .arg(&[eq_name.as_str(), copy_key_name.as_str()])
+        .query(&mut con)
+        .with_context(|| format!("{}:{} failed to run COPY", file!(), line!()))?;
+    assert_eq!(res, 1_usize);
+    let test_node_vector = vec!["1.0"; 3].join(",");
+    let res: String = redis::cmd("usearch.node.add")
+        .arg(&[copy_index_name, "copy_n", test_node_vector.as_str()])
+        .query(&mut con)
+        .with_context(|| format!("{}:{} failed to run usearch.node.add", file!(), line!()))?;
+    assert_eq!(res.to_lowercase(), "ok".to_string());
+    let res: HashMap<String, Value> = redis::cmd("usearch.index.get")
+        .arg(&[copy_index_name])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.get")?;
+    assert_eq!(
+        res.get("name").unwrap(),
+        &Value::Data(copy_key_name.clone().into())
+    );
+    assert_eq!(
+        from_redis_value::<usize>(res.get("index_size").unwrap()).unwrap(),
+        n + 1
+    );
+    assert_eq!(
+        res.get("serialization_file_path").unwrap(),
+        &Value::Data(format!("{}/usearch/{}.{}.idx", curr_dir, select_db, copy_key_name).into()),
+    );
+    let res: HashMap<String, Value> = redis::cmd("usearch.node.get")
+        .arg(&[copy_index_name, "n1"])
+        .query(&mut con)
+        .with_context(|| format!("{}:{} failed to run usearch.node.get", file!(), line!()))?;
+    assert_eq!(
+        res.get("name").unwrap(),
+        &Value::Data(format!("{}.n1", copy_key_name).into())
+    );
+    let res: HashMap<String, Value> = redis::cmd("usearch.index.get")
+        .arg(&[test_index_name])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.get")?;
+    assert_eq!(
+        from_redis_value::<usize>(res.get("index_size").unwrap()).unwrap(),
+        n
+    );
+    let res: usize = redis::cmd("usearch.index.del")
+        .arg(&[copy_index_name])
+        .query(&mut con)
+        .with_context(|| format!("{}:{} failed to run usearch.index.del", file!(), line!()))?;
+    assert_eq!(res, 1_usize);
+
+    // test rename index, node names follow the index key
+    let rename_key_name = "usearch.test_idx2";
+    let _: () = redis::cmd("RENAME")
+        .arg(&[eq_name.as_str(), rename_key_name])
+        .query(&mut con)
+        .with_context(|| format!("{}:{} failed to run RENAME", file!(), line!()))?;
+    let res: HashMap<String, Value> = redis::cmd("usearch.node.get")
+        .arg(&[rename_key_name, "n1"])
+        .query(&mut con)
+        .with_context(|| format!("{}:{} failed to run usearch.node.get", file!(), line!()))?;
+    assert_eq!(
+        res.get("name").unwrap(),
+        &Value::Data(format!("{}.n1", rename_key_name).into())
+    );
+    let res: HashMap<String, Value> = redis::cmd("usearch.index.get")
+        .arg(&[rename_key_name])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.get")?;
+    assert_eq!(
+        res.get("serialization_file_path").unwrap(),
+        &Value::Data(format!("{}/usearch/{}.{}.idx", curr_dir, select_db, rename_key_name).into()),
+    );
+    let _: () = redis::cmd("RENAME")
+        .arg(&[rename_key_name, eq_name.as_str()])
+        .query(&mut con)
+        .with_context(|| format!("{}:{} failed to run RENAME", file!(), line!()))?;
+
     // test search kann
     let k = 10;
     let binding = k.to_string();
@@ -256,6 +506,893 @@ fn test_redisxann_usearch() -> Result<()> {
     if res.is_ok() {
         return Err(anyhow::Error::msg("Should return an error"));
     }
+    let res: Vec<Value> = redis::cmd("usearch.index.list")
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.list")?;
+    assert_eq!(res.len(), 0);
+
+    // test serialization file names are encoded in the managed storage dir
+    let res: String = redis::cmd("usearch.index.create")
+        .arg(&["usearch.a/../../b", "dim", "3"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.create")?;
+    assert_eq!(res.to_lowercase(), "ok".to_string());
+    let res: HashMap<String, Value> = redis::cmd("usearch.index.get")
+        .arg(&["usearch.a/../../b"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.get")?;
+    let storage_dir = format!("{}/usearch", curr_dir);
+    let path = format!("{}/{}.usearch.a%2F..%2F..%2Fb.idx", storage_dir, select_db);
+    assert_eq!(
+        res.get("serialization_file_path").unwrap(),
+        &Value::Data(path.clone().into()),
+    );
+    let _: () = redis::cmd("SAVE")
+        .query(&mut con)
+        .with_context(|| "failed to run SAVE")?;
+    assert!(std::path::Path::new(&path).exists());
+    assert!(!std::path::Path::new(&format!("{}.tmp", path)).exists());
+    let manifest = std::fs::read_to_string(format!("{}/MANIFEST.json", storage_dir))?;
+    assert!(manifest.contains("usearch.a%2F..%2F..%2Fb.idx"));
+    // DEL of the index key removes the file with remove_serialized_file yes
+    let res: usize = redis::cmd("DEL")
+        .arg(&["usearch.a/../../b"])
+        .query(&mut con)
+        .with_context(|| "failed to run DEL")?;
+    assert_eq!(res, 1);
+    assert!(!std::path::Path::new(&path).exists());
+    let manifest = std::fs::read_to_string(format!("{}/MANIFEST.json", storage_dir))?;
+    assert!(!manifest.contains("usearch.a%2F..%2F..%2Fb.idx"));
+
+    // test module keyspace events of the write cmds
+    let _: () = redis::cmd("CONFIG")
+        .arg(&["SET", "notify-keyspace-events", "Ed"])
+        .query(&mut con)
+        .with_context(|| "failed to run CONFIG SET")?;
+    let mut sub_con =
+        get_redis_connection(port).with_context(|| "failed to connect to redis server")?;
+    let mut pubsub = sub_con.as_pubsub();
+    pubsub.set_read_timeout(Some(std::time::Duration::from_millis(500)))?;
+    pubsub.subscribe(format!("__keyevent@{}__:usearch.node.add", select_db))?;
+    let res: String = redis::cmd("usearch.index.create")
+        .arg(&["usearch.notify_idx", "dim", "3"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.create")?;
+    assert_eq!(res.to_lowercase(), "ok".to_string());
+    let res: String = redis::cmd("usearch.node.add")
+        .arg(&["usearch.notify_idx", "n1", "1.0,1.0,1.0"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.node.add")?;
+    assert_eq!(res.to_lowercase(), "ok".to_string());
+    let msg = pubsub.get_message()?;
+    assert_eq!(msg.get_payload::<String>()?, "usearch.notify_idx");
+    // config notify_events no disables them
+    let _: () = redis::cmd("CONFIG")
+        .arg(&["SET", "usearch.notify_events", "no"])
+        .query(&mut con)
+        .with_context(|| "failed to run CONFIG SET")?;
+    let res: String = redis::cmd("usearch.node.add")
+        .arg(&["usearch.notify_idx", "n2", "1.0,1.0,1.0"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.node.add")?;
+    assert_eq!(res.to_lowercase(), "ok".to_string());
+    if pubsub.get_message().is_ok() {
+        return Err(anyhow::Error::msg("Should not notify"));
+    }
+    drop(pubsub);
+    let res: usize = redis::cmd("usearch.index.del")
+        .arg(&["usearch.notify_idx"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.del")?;
+    assert_eq!(res, 1_usize);
 
+    // test partitioned index, vectors are split across partitions and searched in parallel
+    let res: String = redis::cmd("usearch.index.create")
+        .arg(&[
+            "usearch.part_idx",
+            "dim",
+            "3",
+            "m",
+            "10",
+            "efcon",
+            "12",
+            "metric",
+            "l2sq",
+            "quantization",
+            "f32",
+            "partitions",
+            "4",
+        ])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.create")?;
+    assert_eq!(res, "OK");
+    let n = 20;
+    for i in 0..n {
+        let vector = vec![format!("{}.0", i); 3].join(",");
+        let res: String = redis::cmd("usearch.node.add")
+            .arg(&[
+                "usearch.part_idx",
+                format!("n{}", i).as_str(),
+                vector.as_str(),
+            ])
+            .query(&mut con)
+            .with_context(|| "failed to run usearch.node.add")?;
+        assert_eq!(res, "OK");
+    }
+    // partitions are saved in the rdb payload, the restored value has the same digest
+    let digest: Vec<String> = redis::cmd("DEBUG")
+        .arg(&["DIGEST-VALUE", "usearch.part_idx"])
+        .query(&mut con)
+        .with_context(|| "failed to run DEBUG DIGEST-VALUE")?;
+    assert_ne!(digest[0], "0".repeat(40));
+    let dump: Vec<u8> = redis::cmd("DUMP")
+        .arg("usearch.part_idx")
+        .query(&mut con)
+        .with_context(|| "failed to run DUMP")?;
+    let _: () = redis::cmd("RESTORE")
+        .arg("usearch.part_idx")
+        .arg(0)
+        .arg(dump)
+        .arg("REPLACE")
+        .query(&mut con)
+        .with_context(|| "failed to run RESTORE")?;
+    let res: HashMap<String, Value> = redis::cmd("usearch.index.get")
+        .arg(&["usearch.part_idx"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.get")?;
+    assert_eq!(
+        from_redis_value::<usize>(res.get("partitions").unwrap())?,
+        4
+    );
+    let res: Vec<String> = redis::cmd("DEBUG")
+        .arg(&["DIGEST-VALUE", "usearch.part_idx"])
+        .query(&mut con)
+        .with_context(|| "failed to run DEBUG DIGEST-VALUE")?;
+    assert_eq!(res, digest);
+    assert_eq!(
+        from_redis_value::<usize>(res.get("index_size").unwrap())?,
+        n
+    );
+    let res: Reply = redis::cmd("usearch.search.kann")
+        .arg(&["usearch.part_idx", "3", "0.0,0.0,0.0"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.search.kann")?;
+    println!("{res:?}");
+    assert_eq!(res.size, 3);
+    let names: Vec<&str> = res.vals.iter().map(|v| v.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec![
+            "usearch.part_idx.n0",
+            "usearch.part_idx.n1",
+            "usearch.part_idx.n2"
+        ]
+    );
+    // searches can't be dispatched to workers in MULTI, they run on the main thread
+    let res: (Reply,) = redis::pipe()
+        .atomic()
+        .cmd("usearch.search.kann")
+        .arg(&["usearch.part_idx", "3", "0.0,0.0,0.0"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.search.kann in MULTI")?;
+    let multi_names: Vec<&str> = res.0.vals.iter().map(|v| v.name.as_str()).collect();
+    assert_eq!(multi_names, names);
+    let _: usize = redis::cmd("usearch.index.del")
+        .arg(&["usearch.part_idx"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.del")?;
+
+    // big indexes are freed in the lazyfree thread by UNLINK, and unregistered at once
+    let res: String = redis::cmd("usearch.index.create")
+        .arg(&[
+            "usearch.lazy_idx",
+            "dim",
+            "3",
+            "m",
+            "10",
+            "efcon",
+            "12",
+            "metric",
+            "l2sq",
+            "quantization",
+            "f32",
+        ])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.create")?;
+    assert_eq!(res, "OK");
+    for i in 0..100 {
+        let vector = vec![format!("{}.0", i); 3].join(",");
+        let res: String = redis::cmd("usearch.node.add")
+            .arg(&[
+                "usearch.lazy_idx",
+                format!("n{}", i).as_str(),
+                vector.as_str(),
+            ])
+            .query(&mut con)
+            .with_context(|| "failed to run usearch.node.add")?;
+        assert_eq!(res, "OK");
+    }
+    let res: usize = redis::cmd("UNLINK")
+        .arg("usearch.lazy_idx")
+        .query(&mut con)
+        .with_context(|| "failed to run UNLINK")?;
+    assert_eq!(res, 1);
+    let res: Vec<HashMap<String, Value>> = redis::cmd("usearch.index.list")
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.list")?;
+    assert!(!res
+        .iter()
+        .any(|info| info.get("name") == Some(&Value::Data("usearch.lazy_idx".into()))));
+
+    // test rebuild index in place with new options in background
+    let res: String = redis::cmd("usearch.index.create")
+        .arg(&[
+            "usearch.rb_idx",
+            "dim",
+            "3",
+            "m",
+            "10",
+            "efcon",
+            "12",
+            "metric",
+            "l2sq",
+            "quantization",
+            "f32",
+        ])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.create")?;
+    assert_eq!(res, "OK");
+    let n = 10;
+    for i in 0..n {
+        let vector = vec![format!("{}.0", i); 3].join(",");
+        let res: String = redis::cmd("usearch.node.add")
+            .arg(&[
+                "usearch.rb_idx",
+                format!("n{}", i).as_str(),
+                vector.as_str(),
+            ])
+            .query(&mut con)
+            .with_context(|| "failed to run usearch.node.add")?;
+        assert_eq!(res, "OK");
+    }
+    let res: String = redis::cmd("usearch.index.rebuild")
+        .arg(&[
+            "usearch.rb_idx",
+            "usearch.rb_idx",
+            "m",
+            "16",
+            "partitions",
+            "2",
+        ])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.rebuild")?;
+    assert_eq!(res, "OK");
+    let mut rebuilt = false;
+    for _ in 0..100 {
+        let res: HashMap<String, Value> = redis::cmd("usearch.index.get")
+            .arg(&["usearch.rb_idx"])
+            .query(&mut con)
+            .with_context(|| "failed to run usearch.index.get")?;
+        if from_redis_value::<usize>(res.get("partitions").unwrap())? == 2 {
+            assert_eq!(
+                from_redis_value::<usize>(res.get("connectivity").unwrap())?,
+                16
+            );
+            assert_eq!(
+                from_redis_value::<usize>(res.get("index_size").unwrap())?,
+                n
+            );
+            rebuilt = true;
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    assert!(rebuilt);
+    let res: Reply = redis::cmd("usearch.search.kann")
+        .arg(&["usearch.rb_idx", "1", "9.0,9.0,9.0"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.search.kann")?;
+    assert_eq!(res.vals[0].name, "usearch.rb_idx.n9");
+    let _: usize = redis::cmd("usearch.index.del")
+        .arg(&["usearch.rb_idx"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.del")?;
+
+    // test index aliases, the alias is switched between indexes atomically
+    for (i, name) in ["usearch.emb_v1", "usearch.emb_v2"].iter().enumerate() {
+        let res: String = redis::cmd("usearch.index.create")
+            .arg(&[
+                name,
+                "dim",
+                "3",
+                "m",
+                "10",
+                "efcon",
+                "12",
+                "metric",
+                "l2sq",
+                "quantization",
+                "f32",
+            ])
+            .query(&mut con)
+            .with_context(|| "failed to run usearch.index.create")?;
+        assert_eq!(res, "OK");
+        let vector = vec![format!("{}.0", i); 3].join(",");
+        let res: String = redis::cmd("usearch.node.add")
+            .arg(&[name, "n0", vector.as_str()])
+            .query(&mut con)
+            .with_context(|| "failed to run usearch.node.add")?;
+        assert_eq!(res, "OK");
+    }
+    for name in ["usearch.emb_v1", "usearch.emb_v2"] {
+        let res: String = redis::cmd("usearch.alias.set")
+            .arg(&["usearch.emb", name])
+            .query(&mut con)
+            .with_context(|| "failed to run usearch.alias.set")?;
+        assert_eq!(res, "OK");
+        let res: Reply = redis::cmd("usearch.search.kann")
+            .arg(&["usearch.emb", "1", "0.0,0.0,0.0"])
+            .query(&mut con)
+            .with_context(|| "failed to run usearch.search.kann")?;
+        assert_eq!(res.vals[0].name, format!("{}.n0", name));
+    }
+    let res: Vec<(String, String)> = redis::cmd("usearch.alias.list")
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.alias.list")?;
+    assert_eq!(
+        res,
+        vec![("usearch.emb".to_string(), "usearch.emb_v2".to_string())]
+    );
+    let res: Result<String, RedisError> = redis::cmd("usearch.index.create")
+        .arg(&[
+            "usearch.emb",
+            "dim",
+            "3",
+            "m",
+            "10",
+            "efcon",
+            "12",
+            "metric",
+            "l2sq",
+            "quantization",
+            "f32",
+        ])
+        .query(&mut con);
+    if res.is_ok() {
+        return Err(anyhow::Error::msg("Should return an error"));
+    }
+    let res: usize = redis::cmd("usearch.alias.del")
+        .arg(&["usearch.emb"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.alias.del")?;
+    assert_eq!(res, 1);
+    let res: Result<HashMap<String, Value>, RedisError> = redis::cmd("usearch.index.get")
+        .arg(&["usearch.emb"])
+        .query(&mut con);
+    if res.is_ok() {
+        return Err(anyhow::Error::msg("Should return an error"));
+    }
+    // aliases of deleted and renamed indexes are removed
+    for (alias_name, name) in [
+        ("usearch.emb_a", "usearch.emb_v1"),
+        ("usearch.emb_b", "usearch.emb_v2"),
+    ] {
+        let res: String = redis::cmd("usearch.alias.set")
+            .arg(&[alias_name, name])
+            .query(&mut con)
+            .with_context(|| "failed to run usearch.alias.set")?;
+        assert_eq!(res, "OK");
+    }
+    let _: () = redis::cmd("RENAME")
+        .arg(&["usearch.emb_v2", "usearch.emb_v3"])
+        .query(&mut con)
+        .with_context(|| "failed to run RENAME")?;
+    let res: Vec<(String, String)> = redis::cmd("usearch.alias.list")
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.alias.list")?;
+    assert_eq!(
+        res,
+        vec![("usearch.emb_a".to_string(), "usearch.emb_v1".to_string())]
+    );
+    let _: () = redis::cmd("RENAME")
+        .arg(&["usearch.emb_v3", "usearch.emb_v2"])
+        .query(&mut con)
+        .with_context(|| "failed to run RENAME")?;
+    let res: usize = redis::cmd("usearch.index.del")
+        .arg(&["usearch.emb_a"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.del")?;
+    assert_eq!(res, 1);
+    let res: Vec<(String, String)> = redis::cmd("usearch.alias.list")
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.alias.list")?;
+    assert!(res.is_empty());
+    let _: usize = redis::cmd("usearch.index.del")
+        .arg(&["usearch.emb_v2"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.del")?;
+
+    // test the embedding model tag and the model assertion
+    let res: String = redis::cmd("usearch.index.create")
+        .arg(&[
+            "usearch.model_idx",
+            "dim",
+            "3",
+            "m",
+            "10",
+            "efcon",
+            "12",
+            "metric",
+            "l2sq",
+            "quantization",
+            "f32",
+            "model",
+            "m1",
+            "version",
+            "1",
+        ])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.create")?;
+    assert_eq!(res, "OK");
+    let res: HashMap<String, Value> = redis::cmd("usearch.index.get")
+        .arg(&["usearch.model_idx"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.get")?;
+    assert_eq!(res["model"], Value::Data("m1".into()));
+    assert_eq!(res["version"], Value::Data("1".into()));
+    let res: String = redis::cmd("usearch.node.add")
+        .arg(&["usearch.model_idx", "n1", "1.0,1.0,1.0", "model", "m1"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.node.add")?;
+    assert_eq!(res, "OK");
+    let res: Result<String, RedisError> = redis::cmd("usearch.node.add")
+        .arg(&["usearch.model_idx", "n2", "1.0,1.0,1.0", "model", "m2"])
+        .query(&mut con);
+    let err = res
+        .err()
+        .ok_or_else(|| anyhow::Error::msg("Should return an error"))?;
+    assert!(err.to_string().contains("model m2 does not match"));
+    let res: Result<String, RedisError> = redis::cmd("usearch.node.add")
+        .arg(&["usearch.model_idx", "n2", "1.0,1.0"])
+        .query(&mut con);
+    let err = res
+        .err()
+        .ok_or_else(|| anyhow::Error::msg("Should return an error"))?;
+    assert!(err
+        .to_string()
+        .contains("vector dimensions 2 does not match"));
+    let res: Reply = redis::cmd("usearch.search.kann")
+        .arg(&["usearch.model_idx", "1", "1.0,1.0,1.0", "", "model", "m1"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.search.kann")?;
+    assert_eq!(res.vals[0].name, "usearch.model_idx.n1");
+    let res: Result<Reply, RedisError> = redis::cmd("usearch.search.kann")
+        .arg(&["usearch.model_idx", "1", "1.0,1.0,1.0", "", "model", "m2"])
+        .query(&mut con);
+    if res.is_ok() {
+        return Err(anyhow::Error::msg("Should return an error"));
+    }
+    let _: usize = redis::cmd("usearch.index.del")
+        .arg(&["usearch.model_idx"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.del")?;
+
+    // semantic errors reply with error codes instead of wrong arity
+    let res: String = redis::cmd("usearch.index.create")
+        .arg(&[
+            "usearch.err_idx",
+            "dim",
+            "3",
+            "m",
+            "10",
+            "efcon",
+            "12",
+            "metric",
+            "l2sq",
+            "quantization",
+            "f32",
+        ])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.create")?;
+    assert_eq!(res, "OK");
+    for (cmd, args, code) in [
+        (
+            "usearch.node.add",
+            vec!["usearch.err_idx", "n1", "1.0,1.0"],
+            "DIMMISMATCH",
+        ),
+        (
+            "usearch.node.add",
+            vec!["usearch.err_idx", "n1", "1.0,x,1.0"],
+            "BADPARAM",
+        ),
+        (
+            "usearch.node.add",
+            vec!["usearch.err_idx", "n1", "1.0,1.0,1.0", "ids", "1"],
+            "BADPARAM",
+        ),
+        (
+            "usearch.node.get",
+            vec!["usearch.err_idx", "n_none"],
+            "NONODE",
+        ),
+        (
+            "usearch.node.get_id",
+            vec!["usearch.err_idx", "404"],
+            "NONODE",
+        ),
+        ("usearch.index.get", vec!["usearch.idx_none"], "NOINDEX"),
+        ("usearch.index.get", vec!["err_idx"], "BADPARAM"),
+        (
+            "usearch.search.kann",
+            vec!["usearch.err_idx", "1", "1.0,1.0,1.0", "", "ef_search"],
+            "BADPARAM",
+        ),
+        (
+            "usearch.search.kann",
+            vec!["usearch.err_idx", "1", "1.0,1.0,1.0", "", "ef", "10"],
+            "BADPARAM",
+        ),
+    ] {
+        let res: Result<Value, RedisError> = redis::cmd(cmd).arg(&args).query(&mut con);
+        let err = res
+            .err()
+            .ok_or_else(|| anyhow::Error::msg("Should return an error"))?;
+        assert_eq!(err.code(), Some(code));
+    }
+    let _: usize = redis::cmd("usearch.index.del")
+        .arg(&["usearch.err_idx"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.index.del")?;
+
+    // test acl key permissions on the index key
+    for name in ["usearch.tenantA_idx", "usearch.tenantB_idx"] {
+        let _: () = redis::cmd("usearch.index.create")
+            .arg(&[
+                name,
+                "dim",
+                "3",
+                "m",
+                "10",
+                "efcon",
+                "12",
+                "metric",
+                "l2sq",
+                "quantization",
+                "f32",
+            ])
+            .query(&mut con)
+            .with_context(|| "failed to run usearch.index.create")?;
+    }
+    let _: () = redis::cmd("ACL")
+        .arg(&[
+            "SETUSER",
+            "tenantA",
+            "on",
+            ">pass",
+            "~usearch.tenantA*",
+            "+@all",
+        ])
+        .query(&mut con)
+        .with_context(|| "failed to run ACL SETUSER")?;
+    let mut tenant_con =
+        get_redis_connection(port).with_context(|| "failed to connect to redis server")?;
+    let _: () = redis::cmd("AUTH")
+        .arg(&["tenantA", "pass"])
+        .query(&mut tenant_con)
+        .with_context(|| "failed to run AUTH")?;
+    let _: HashMap<String, Value> = redis::cmd("usearch.index.get")
+        .arg(&["usearch.tenantA_idx"])
+        .query(&mut tenant_con)
+        .with_context(|| "failed to run usearch.index.get")?;
+    let res: Result<HashMap<String, Value>, RedisError> = redis::cmd("usearch.index.get")
+        .arg(&["usearch.tenantB_idx"])
+        .query(&mut tenant_con);
+    match res {
+        Err(e) => assert_eq!(e.code(), Some("NOPERM")),
+        Ok(_) => return Err(anyhow::Error::msg("Should return an error")),
+    }
+    let res: Vec<Value> = redis::cmd("usearch.index.list")
+        .query(&mut tenant_con)
+        .with_context(|| "failed to run usearch.index.list")?;
+    assert_eq!(res.len(), 1);
+    // the index which an alias is resolved to is checked too
+    let _: () = redis::cmd("usearch.alias.set")
+        .arg(&["usearch.tenantA_alias", "usearch.tenantB_idx"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.alias.set")?;
+    let res: Result<HashMap<String, Value>, RedisError> = redis::cmd("usearch.index.get")
+        .arg(&["usearch.tenantA_alias"])
+        .query(&mut tenant_con);
+    match res {
+        Err(e) => assert_eq!(e.code(), Some("NOPERM")),
+        Ok(_) => return Err(anyhow::Error::msg("Should return an error")),
+    }
+    let _: usize = redis::cmd("usearch.alias.del")
+        .arg(&["usearch.tenantA_alias"])
+        .query(&mut con)
+        .with_context(|| "failed to run usearch.alias.del")?;
+    for name in ["usearch.tenantA_idx", "usearch.tenantB_idx"] {
+        let _: usize = redis::cmd("usearch.index.del")
+            .arg(&[name])
+            .query(&mut con)
+            .with_context(|| "failed to run usearch.index.del")?;
+    }
+
+    // test module info section
+    let res: String = redis::cmd("INFO")
+        .arg(&["usearch"])
+        .query(&mut con)
+        .with_context(|| "failed to run INFO")?;
+    assert!(res.contains("# usearch_redisxann"));
+    for field in [
+        "indexes:",
+        "vectors:",
+        "insert_calls:",
+        "search_p99_usec:",
+        "last_save_usec:",
+    ] {
+        assert!(res.contains(field), "INFO misses {}", field);
+    }
+    assert!(!res.contains("insert_calls:0\r\n"));
+
+    Ok(())
+}
+
+#[test]
+fn test_redisxann_usearch_dkann() -> Result<()> {
+    let curr_dir = env::current_dir().unwrap().to_string_lossy().to_string();
+    let ports: [u16; 2] = [6480, 6481];
+    let mut guards: Vec<utils::ChildGuard> = vec![];
+    let mut cons = vec![];
+    for port in ports {
+        let conf_file = format!("nodes-{port}.conf");
+        let _ = std::fs::remove_file(&conf_file);
+        guards.push(
+            start_redis_server_with_module(
+                "redisxann_usearch",
+                port,
+                vec![
+                    "serialization_file_path_dir",
+                    curr_dir.as_str(),
+                    "remove_serialized_file",
+                    "yes",
+                    "--cluster-enabled",
+                    "yes",
+                    "--cluster-config-file",
+                    conf_file.as_str(),
+                ],
+            )
+            .with_context(|| "failed to start redis server")?,
+        );
+        cons.push(get_redis_connection(port).with_context(|| "failed to connect to redis server")?);
+    }
+
+    // the 1st node serves slots 0-8191, the 2nd node serves slots 8192-16383
+    let slot_ranges = [["0", "8191"], ["8192", "16383"]];
+    for (con, slots) in cons.iter_mut().zip(slot_ranges.iter()) {
+        let _: () = redis::cmd("CLUSTER")
+            .arg("ADDSLOTSRANGE")
+            .arg(slots)
+            .query(con)
+            .with_context(|| "failed to run CLUSTER ADDSLOTSRANGE")?;
+    }
+    let _: () = redis::cmd("CLUSTER")
+        .arg(&["MEET", "127.0.0.1", ports[1].to_string().as_str()])
+        .query(&mut cons[0])
+        .with_context(|| "failed to run CLUSTER MEET")?;
+    // wait for the cluster state at most 10s
+    for con in cons.iter_mut() {
+        let mut ok = false;
+        for _ in 0..100 {
+            let info: String = redis::cmd("CLUSTER").arg("INFO").query(con)?;
+            let nodes: String = redis::cmd("CLUSTER").arg("NODES").query(con)?;
+            if info.contains("cluster_state:ok") && nodes.lines().count() == 2 {
+                ok = true;
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        assert!(ok, "cluster state isn't ok in 10s");
+    }
+
+    // create an index on each node, the index name arg is the cmd key which must be in the node's slots
+    let mut index_names: Vec<String> = vec![];
+    for (i, con) in cons.iter_mut().enumerate() {
+        let name = (0..)
+            .map(|n| format!("usearch.emb_{n}"))
+            .find(|name| {
+                let slot: u16 = redis::cmd("CLUSTER")
+                    .arg(&["KEYSLOT", name.as_str()])
+                    .query(con)
+                    .unwrap();
+                (slot < 8192) == (i == 0) && !index_names.contains(name)
+            })
+            .unwrap();
+        let res: String = redis::cmd("usearch.index.create")
+            .arg(&[
+                name.as_str(),
+                "dim",
+                "3",
+                "m",
+                "10",
+                "efcon",
+                "12",
+                "metric",
+                "l2sq",
+                "quantization",
+                "f32",
+            ])
+            .query(con)
+            .with_context(|| "failed to run usearch.index.create")?;
+        assert_eq!(res, "OK");
+        let vector = format!("{i}.0,{i}.0,{i}.0");
+        let res: String = redis::cmd("usearch.node.add")
+            .arg(&[name.as_str(), format!("n{i}").as_str(), vector.as_str()])
+            .query(con)
+            .with_context(|| "failed to run usearch.node.add")?;
+        assert_eq!(res, "OK");
+        index_names.push(name);
+    }
+
+    // results are merged from both shards, ordered by similarity
+    let res: Vec<Value> = redis::cmd("usearch.search.dkann")
+        .arg(&["usearch.emb_*", "10", "0.0,0.0,0.0"])
+        .query(&mut cons[0])
+        .with_context(|| "failed to run usearch.search.dkann")?;
+    println!("{res:?}");
+    let count: usize = from_redis_value(&res[0])?;
+    let partial: usize = from_redis_value(&res[1])?;
+    assert_eq!(count, 2);
+    assert_eq!(partial, 0);
+    let mut names: Vec<String> = vec![];
+    for val in res[2..].iter() {
+        let fields: Vec<Value> = from_redis_value(val)?;
+        for field in fields.chunks(2) {
+            let key: String = from_redis_value(&field[0])?;
+            if key == "name" {
+                names.push(from_redis_value(&field[1])?);
+            }
+        }
+    }
+    assert_eq!(names, vec!["n0", "n1"]);
+
+    let res: Vec<Value> = redis::cmd("usearch.search.dkann")
+        .arg(&["usearch.emb_*", "1", "1.0,1.0,1.0", "timeout", "500"])
+        .query(&mut cons[1])
+        .with_context(|| "failed to run usearch.search.dkann")?;
+    let count: usize = from_redis_value(&res[0])?;
+    assert_eq!(count, 1);
+
+    for (con, name) in cons.iter_mut().zip(index_names.iter()) {
+        let _: usize = redis::cmd("usearch.index.del")
+            .arg(&[name])
+            .query(con)
+            .with_context(|| "failed to run usearch.index.del")?;
+    }
+    drop(guards);
+    for port in ports {
+        let _ = std::fs::remove_file(format!("nodes-{port}.conf"));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_redisxann_usearch_rebuild_replica() -> Result<()> {
+    let curr_dir = env::current_dir().unwrap().to_string_lossy().to_string();
+    let ports: [u16; 2] = [6482, 6483];
+    let master_port = ports[0].to_string();
+    let mut guards: Vec<utils::ChildGuard> = vec![];
+    let mut cons = vec![];
+    for port in ports {
+        let dir = format!("{}/replica-{}", curr_dir, port);
+        let dbfilename = format!("dump-{port}.rdb");
+        let mut args = vec![
+            "serialization_file_path_dir",
+            dir.as_str(),
+            "remove_serialized_file",
+            "yes",
+            "--dbfilename",
+            dbfilename.as_str(),
+        ];
+        if port != ports[0] {
+            args.extend(["--replicaof", "127.0.0.1", master_port.as_str()]);
+        }
+        guards.push(
+            start_redis_server_with_module("redisxann_usearch", port, args)
+                .with_context(|| "failed to start redis server")?,
+        );
+        cons.push(get_redis_connection(port).with_context(|| "failed to connect to redis server")?);
+    }
+    // wait for the replica link at most 10s
+    let mut ok = false;
+    for _ in 0..100 {
+        let info: String = redis::cmd("INFO").arg("replication").query(&mut cons[1])?;
+        if info.contains("master_link_status:up") {
+            ok = true;
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+    assert!(ok, "replica link isn't up in 10s");
+
+    // clients can't call the swap cmd which is replicated by the rebuild
+    let res: Result<String, RedisError> = redis::cmd("usearch.index.swap")
+        .arg(&["usearch.rb_dst", "{}", "{}", "{}", "[]", "buf"])
+        .query(&mut cons[0]);
+    assert!(res.is_err());
+
+    let res: String = redis::cmd("usearch.index.create")
+        .arg(&["usearch.rb_src", "dim", "3"])
+        .query(&mut cons[0])
+        .with_context(|| "failed to run usearch.index.create")?;
+    assert_eq!(res, "OK");
+    let n = 10;
+    for i in 0..n {
+        let vector = vec![format!("{}.0", i); 3].join(",");
+        let res: String = redis::cmd("usearch.node.add")
+            .arg(&[
+                "usearch.rb_src",
+                format!("n{}", i).as_str(),
+                vector.as_str(),
+            ])
+            .query(&mut cons[0])
+            .with_context(|| "failed to run usearch.node.add")?;
+        assert_eq!(res, "OK");
+    }
+    let res: String = redis::cmd("usearch.index.rebuild")
+        .arg(&["usearch.rb_src", "usearch.rb_dst", "partitions", "2"])
+        .query(&mut cons[0])
+        .with_context(|| "failed to run usearch.index.rebuild")?;
+    assert_eq!(res, "OK");
+    let mut rebuilt = false;
+    for _ in 0..100 {
+        let res: Result<HashMap<String, Value>, RedisError> = redis::cmd("usearch.index.get")
+            .arg(&["usearch.rb_dst"])
+            .query(&mut cons[0]);
+        if res.is_ok() {
+            rebuilt = true;
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+    assert!(rebuilt);
+
+    // the writes to the dst index after the swap are applied to the swapped index on the replica
+    let res: String = redis::cmd("usearch.node.add")
+        .arg(&["usearch.rb_dst", "n10", "10.0,10.0,10.0"])
+        .query(&mut cons[0])
+        .with_context(|| "failed to run usearch.node.add")?;
+    assert_eq!(res, "OK");
+    let res: usize = redis::cmd("WAIT")
+        .arg(&["1", "5000"])
+        .query(&mut cons[0])
+        .with_context(|| "failed to run WAIT")?;
+    assert_eq!(res, 1);
+    let res: HashMap<String, Value> = redis::cmd("usearch.index.get")
+        .arg(&["usearch.rb_dst"])
+        .query(&mut cons[1])
+        .with_context(|| "failed to run usearch.index.get")?;
+    assert_eq!(
+        from_redis_value::<usize>(res.get("partitions").unwrap())?,
+        2
+    );
+    assert_eq!(
+        from_redis_value::<usize>(res.get("index_size").unwrap())?,
+        n + 1
+    );
+    let res: Reply = redis::cmd("usearch.search.kann")
+        .arg(&["usearch.rb_dst", "1", "9.0,9.0,9.0"])
+        .query(&mut cons[1])
+        .with_context(|| "failed to run usearch.search.kann")?;
+    assert_eq!(res.vals[0].name, "usearch.rb_dst.n9");
+
+    for name in ["usearch.rb_src", "usearch.rb_dst"] {
+        let _: usize = redis::cmd("usearch.index.del")
+            .arg(&[name])
+            .query(&mut cons[0])
+            .with_context(|| "failed to run usearch.index.del")?;
+    }
     Ok(())
 }
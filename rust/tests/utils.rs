@@ -54,7 +54,15 @@ pub fn start_redis_server_with_module(
     let module_path = format!("{}", module_path.display());
 
     let port_str = port.to_string();
-    let mut args = vec!["--port", &port_str, "--loadmodule", module_path.as_str()];
+    // DEBUG DIGEST-VALUE is used to check the values after rdb round trips
+    let mut args = vec![
+        "--port",
+        &port_str,
+        "--enable-debug-command",
+        "local",
+        "--loadmodule",
+        module_path.as_str(),
+    ];
     args.extend(other_args);
 
     let redis_server = Command::new("redis-server")
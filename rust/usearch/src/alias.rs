@@ -0,0 +1,122 @@
+use std::collections::{BTreeMap, HashMap};
+use std::os::raw::c_int;
+use std::sync::RwLock;
+
+use redis_module::{logging, raw};
+
+use modcore::rdb;
+
+lazy_static! {
+    // index aliases in each db, alias key name -> index key name, e.g. usearch.emb -> usearch.emb_v2,
+    // they aren't keys, so they're saved in the rdb aux data.
+    static ref ALIASES: RwLock<HashMap<i32, BTreeMap<String, String>>> = RwLock::new(HashMap::new());
+}
+
+// resolve the alias to the index key name, the name is returned if it isn't an alias
+pub fn resolve(db: i32, name: String) -> String {
+    match ALIASES.read().unwrap().get(&db).and_then(|a| a.get(&name)) {
+        Some(index_name) => index_name.clone(),
+        None => name,
+    }
+}
+
+pub fn contains(db: i32, alias: &str) -> bool {
+    match ALIASES.read().unwrap().get(&db) {
+        Some(aliases) => aliases.contains_key(alias),
+        None => false,
+    }
+}
+
+// set the alias to the index, return the previous index of the alias
+pub fn set(db: i32, alias: &str, index_name: &str) -> Option<String> {
+    ALIASES
+        .write()
+        .unwrap()
+        .entry(db)
+        .or_default()
+        .insert(alias.to_string(), index_name.to_string())
+}
+
+pub fn remove(db: i32, alias: &str) -> bool {
+    match ALIASES.write().unwrap().get_mut(&db) {
+        Some(aliases) => aliases.remove(alias).is_some(),
+        None => false,
+    }
+}
+
+// remove_index
+// remove the aliases of the index which is deleted, renamed or moved out of db,
+// so an alias never resolves to another index created with the same name later.
+// return the count of removed aliases
+pub fn remove_index(db: i32, index_name: &str) -> usize {
+    match ALIASES.write().unwrap().get_mut(&db) {
+        Some(aliases) => {
+            let n = aliases.len();
+            aliases.retain(|_, name| name != index_name);
+            n - aliases.len()
+        }
+        None => 0,
+    }
+}
+
+// sorted (alias, index) key names in db
+pub fn list(db: i32) -> Vec<(String, String)> {
+    match ALIASES.read().unwrap().get(&db) {
+        Some(aliases) => aliases
+            .iter()
+            .map(|(a, i)| (a.clone(), i.clone()))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+// flush db aliases, db -1 flush all dbs
+pub fn flush(db: i32) {
+    let mut aliases = ALIASES.write().unwrap();
+    if db == -1 {
+        aliases.clear();
+    } else {
+        aliases.remove(&db);
+    }
+}
+
+pub fn swap(db1: i32, db2: i32) {
+    let mut aliases = ALIASES.write().unwrap();
+    let aliases1 = aliases.remove(&db1);
+    let aliases2 = aliases.remove(&db2);
+    if let Some(a) = aliases1 {
+        aliases.insert(db2, a);
+    }
+    if let Some(a) = aliases2 {
+        aliases.insert(db1, a);
+    }
+}
+
+// aliases are saved as json before the keys in rdb
+pub unsafe extern "C" fn aux_save(rdb: *mut raw::RedisModuleIO, when: c_int) {
+    if when != raw::REDISMODULE_AUX_BEFORE_RDB as c_int {
+        return;
+    }
+    let json = serde_json::to_string(&*ALIASES.read().unwrap()).unwrap_or_default();
+    raw::save_string(rdb, json.as_str());
+}
+
+pub unsafe extern "C" fn aux_load(
+    rdb: *mut raw::RedisModuleIO,
+    _encver: c_int,
+    when: c_int,
+) -> c_int {
+    if when != raw::REDISMODULE_AUX_BEFORE_RDB as c_int {
+        return raw::REDISMODULE_OK as c_int;
+    }
+    match rdb::load_json(rdb, "aliases") {
+        Ok(aliases) => {
+            *ALIASES.write().unwrap() = aliases;
+            raw::REDISMODULE_OK as c_int
+        }
+        Err(e) => {
+            logging::log_warning(format!("load index aliases err {}", e));
+            raw::REDISMODULE_ERR as c_int
+        }
+    }
+}
@@ -0,0 +1,499 @@
+use std::collections::HashMap;
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int, c_uchar, c_void};
+use std::ptr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use modcore::acl::{check_user_key_acl, current_user_name};
+use modcore::{lock, pool, registry};
+use redis_module::{raw, Context, RedisError, RedisValue};
+use serde::{Deserialize, Serialize};
+
+use crate::get_selected_db;
+use crate::types::*;
+
+// cluster message types, the receivers are registered by module
+const MSG_DKANN_REQUEST: u8 = 1;
+const MSG_DKANN_RESPONSE: u8 = 2;
+pub static DKANN_DEFAULT_TIMEOUT_MS: i64 = 1000;
+
+lazy_static! {
+    // dkann searches waiting for shard responses, search id -> pending search
+    static ref PENDING_SEARCHES: Mutex<HashMap<u64, PendingSearch>> = Mutex::new(HashMap::new());
+}
+static SEARCH_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Serialize, Deserialize)]
+struct DkannRequest {
+    id: u64,
+    db: i32,
+    user: Option<String>, // coordinator user, acl key permissions are checked on each shard
+    pattern: String,
+    k: usize,
+    vector: Vec<f64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct DkannResponse {
+    id: u64,
+    matches: Vec<DkannMatch>,
+    errors: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct DkannMatch {
+    pub index: String,
+    pub id: usize,
+    pub name: String,
+    pub sim: f64,
+}
+
+// DkannMatch -> RedisValue
+impl From<DkannMatch> for RedisValue {
+    fn from(m: DkannMatch) -> Self {
+        let mut reply: Vec<RedisValue> = Vec::new();
+
+        reply.push("similarity".into());
+        reply.push(m.sim.into());
+
+        reply.push("name".into());
+        reply.push(m.name.into());
+
+        reply.push("id".into());
+        reply.push(m.id.into());
+
+        reply.push("index".into());
+        reply.push(m.index.into());
+
+        reply.into()
+    }
+}
+
+struct PendingSearch {
+    bc: *mut raw::RedisModuleBlockedClient,
+    k: usize,
+    waiting: usize, // count of shards which don't respond
+    matches: Vec<DkannMatch>,
+    errors: Vec<String>,
+}
+
+// blocked client handle is only used on the main thread
+unsafe impl Send for PendingSearch {}
+
+// DkannReply
+// merged top K matches, partial if some shards don't respond or fail
+pub struct DkannReply {
+    pub matches: Vec<DkannMatch>,
+    pub errors: Vec<String>,
+    pub partial: bool,
+}
+
+impl DkannReply {
+    pub fn new(mut matches: Vec<DkannMatch>, errors: Vec<String>, k: usize) -> Self {
+        matches.sort_by(|a, b| a.sim.total_cmp(&b.sim));
+        matches.truncate(k);
+        let partial = !errors.is_empty();
+        DkannReply {
+            matches,
+            errors,
+            partial,
+        }
+    }
+}
+
+// DkannReply -> RedisValue
+// [count, partial, match...]
+impl From<DkannReply> for RedisValue {
+    fn from(r: DkannReply) -> Self {
+        let mut reply: Vec<RedisValue> = Vec::with_capacity(r.matches.len() + 2);
+        reply.push(r.matches.len().into());
+        reply.push((r.partial as usize).into());
+        reply.extend(r.matches.into_iter().map(|m| m.into()));
+        reply.into()
+    }
+}
+
+// glob_match
+// match key name with glob pattern, support * and ?
+pub fn glob_match(pattern: &[u8], s: &[u8]) -> bool {
+    let (mut p, mut i) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while i < s.len() {
+        if p < pattern.len() && (pattern[p] == b'?' || pattern[p] == s[i]) {
+            p += 1;
+            i += 1;
+        } else if p < pattern.len() && pattern[p] == b'*' {
+            star = Some((p, i));
+            p += 1;
+        } else if let Some((sp, si)) = star {
+            p = sp + 1;
+            i = si + 1;
+            star = Some((sp, si + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == b'*')
+}
+
+// search_local
+// search top K of each index matched the pattern in the selected db of this node
+pub fn search_local(
+    ctx: &Context,
+    user: Option<&str>,
+    pattern: &str,
+    k: usize,
+    vector: &[f64],
+) -> (Vec<DkannMatch>, Vec<String>) {
+    let db = get_selected_db(ctx);
+    let mut matches = Vec::new();
+    let mut errors = Vec::new();
+    for name in registry::list(db) {
+        if !glob_match(pattern.as_bytes(), name.as_bytes()) {
+            continue;
+        }
+        let index_name = ctx.create_string(name.clone());
+        if let Some(user) = user {
+            if check_user_key_acl(ctx, user, &index_name, false).is_err() {
+                continue;
+            }
+        }
+        let key = ctx.open_key(&index_name);
+        let index_redis = match key.get_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE) {
+            Ok(Some(index_redis)) => index_redis,
+            _ => continue,
+        };
+        let dimensions = index_redis.index_opts.dimensions;
+        if dimensions != vector.len() {
+            errors.push(format!(
+                "Index: {} dimensions {} != query dimensions {}",
+                name,
+                dimensions,
+                vector.len()
+            ));
+            continue;
+        }
+
+        // the expansion maybe changed by an ef_search query on a worker
+        let res = lock::read_lock(ctx, &name, &index_redis.expansion_lock)
+            .map_err(|e| e.to_string())
+            .and_then(|_guard| index_redis.search(vector, k));
+        match res {
+            Ok(res) => {
+                for i in 0..res.keys.len() {
+                    // removed node
+                    if res.keys[i] == std::u64::MAX {
+                        continue;
+                    }
+                    let id = res.keys[i] as usize;
+                    matches.push(DkannMatch {
+                        index: name.clone(),
+                        id,
+                        name: index_redis
+                            .node_id_name_map
+                            .get(&id)
+                            .cloned()
+                            .unwrap_or_default(),
+                        sim: res.distances[i] as f64,
+                    });
+                }
+            }
+            Err(e) => errors.push(format!("Index {} search ann err {}", name, e)),
+        }
+    }
+    (matches, errors)
+}
+
+pub fn is_cluster(ctx: &Context) -> bool {
+    let flags = unsafe { raw::RedisModule_GetContextFlags.unwrap()(ctx.ctx) };
+    flags & raw::REDISMODULE_CTX_FLAGS_CLUSTER as c_int != 0
+}
+
+// node id isn't NUL terminated
+unsafe fn node_id(id: *const c_char) -> Option<CString> {
+    let id = std::slice::from_raw_parts(id as *const u8, raw::REDISMODULE_NODE_ID_LEN as usize);
+    CString::new(id).ok()
+}
+
+// other master nodes which are not failed, each master holds a shard
+fn other_master_ids(ctx: &Context) -> Vec<CString> {
+    let mut ids = Vec::new();
+    unsafe {
+        let mut num_nodes: usize = 0;
+        let nodes = raw::RedisModule_GetClusterNodesList.unwrap()(ctx.ctx, &mut num_nodes);
+        if nodes.is_null() {
+            return ids;
+        }
+        for i in 0..num_nodes {
+            let id = match node_id(*nodes.add(i)) {
+                Some(id) => id,
+                None => continue,
+            };
+            let mut flags: c_int = 0;
+            let res = raw::RedisModule_GetClusterNodeInfo.unwrap()(
+                ctx.ctx,
+                id.as_ptr(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+                &mut flags,
+            );
+            if res != raw::REDISMODULE_OK as c_int {
+                continue;
+            }
+            let skip = (raw::REDISMODULE_NODE_MYSELF
+                | raw::REDISMODULE_NODE_PFAIL
+                | raw::REDISMODULE_NODE_FAIL) as c_int;
+            if flags & raw::REDISMODULE_NODE_MASTER as c_int == 0 || flags & skip != 0 {
+                continue;
+            }
+            ids.push(id);
+        }
+        raw::RedisModule_FreeClusterNodesList.unwrap()(nodes);
+    }
+    ids
+}
+
+fn send_message(ctx: &Context, target: &CString, msg_type: u8, payload: &[u8]) -> bool {
+    let res = unsafe {
+        raw::RedisModule_SendClusterMessage.unwrap()(
+            ctx.ctx,
+            target.as_ptr(),
+            msg_type,
+            payload.as_ptr() as *const c_char,
+            payload.len() as u32,
+        )
+    };
+    res == raw::REDISMODULE_OK as c_int
+}
+
+// dkann
+// search local indexes, then fan out the request to other shards with the client blocked,
+// the client is unblocked when all shards respond, or replied with partial results on timeout.
+// note: the client which can't be blocked (MULTI, lua) is replied with the local results.
+pub fn dkann(
+    ctx: &Context,
+    pattern: String,
+    k: usize,
+    vector: Vec<f64>,
+    timeout_ms: i64,
+) -> Result<RedisValue, RedisError> {
+    let user = current_user_name(ctx);
+    let (matches, mut errors) = search_local(ctx, user.as_deref(), &pattern, k, &vector);
+    if !is_cluster(ctx) || !pool::can_block(ctx) {
+        return Ok(DkannReply::new(matches, errors, k).into());
+    }
+    let nodes = other_master_ids(ctx);
+    if nodes.is_empty() {
+        return Ok(DkannReply::new(matches, errors, k).into());
+    }
+
+    let req = DkannRequest {
+        id: SEARCH_ID.fetch_add(1, Ordering::Relaxed),
+        db: get_selected_db(ctx),
+        user,
+        pattern,
+        k,
+        vector,
+    };
+    let payload = serde_json::to_vec(&req).map_err(|e| RedisError::String(e.to_string()))?;
+
+    let bc = unsafe {
+        let bc = raw::RedisModule_BlockClient.unwrap()(
+            ctx.ctx,
+            Some(dkann_reply),
+            Some(dkann_timeout),
+            Some(free_dkann_reply),
+            timeout_ms,
+        );
+        raw::RedisModule_SetDisconnectCallback.unwrap()(bc, Some(dkann_disconnected));
+        bc
+    };
+    let mut waiting = 0;
+    for node in nodes.iter() {
+        if send_message(ctx, node, MSG_DKANN_REQUEST, &payload) {
+            waiting += 1;
+        } else {
+            errors.push(format!("send dkann request to node {:?} err", node));
+        }
+    }
+
+    let pending = PendingSearch {
+        bc,
+        k,
+        waiting,
+        matches,
+        errors,
+    };
+    if waiting == 0 {
+        unblock(pending);
+    } else {
+        PENDING_SEARCHES.lock().unwrap().insert(req.id, pending);
+    }
+
+    Ok(RedisValue::NoReply)
+}
+
+fn unblock(pending: PendingSearch) {
+    let reply = Box::new(DkannReply::new(pending.matches, pending.errors, pending.k));
+    unsafe {
+        raw::RedisModule_UnblockClient.unwrap()(pending.bc, Box::into_raw(reply) as *mut c_void);
+    }
+}
+
+unsafe extern "C" fn dkann_reply(
+    ctx: *mut raw::RedisModuleCtx,
+    _argv: *mut *mut raw::RedisModuleString,
+    _argc: c_int,
+) -> c_int {
+    let reply = raw::RedisModule_GetBlockedClientPrivateData.unwrap()(ctx) as *mut DkannReply;
+    let ctx = Context::new(ctx);
+    if reply.is_null() {
+        return ctx.reply(Err(RedisError::Str("dkann reply is null"))) as c_int;
+    }
+    // private data is freed by free_dkann_reply
+    let reply = &mut *reply;
+    let reply = DkannReply {
+        matches: std::mem::take(&mut reply.matches),
+        errors: std::mem::take(&mut reply.errors),
+        partial: reply.partial,
+    };
+    for e in reply.errors.iter() {
+        ctx.log_warning(format!("dkann partial err {}", e).as_str());
+    }
+    ctx.reply(Ok(reply.into())) as c_int
+}
+
+// remove the pending search of the blocked client, None if it's unblocked by the shard responses
+fn remove_pending(bc: *mut raw::RedisModuleBlockedClient) -> Option<PendingSearch> {
+    let mut searches = PENDING_SEARCHES.lock().unwrap();
+    let id = searches
+        .iter()
+        .find(|(_, search)| search.bc == bc)
+        .map(|(id, _)| *id);
+    id.and_then(|id| searches.remove(&id))
+}
+
+// the timed out client is replied with the partial results, redis still requires UnblockClient
+// to free the blocked client handle, the reply callback isn't called for the timed out client.
+unsafe extern "C" fn dkann_timeout(
+    ctx: *mut raw::RedisModuleCtx,
+    _argv: *mut *mut raw::RedisModuleString,
+    _argc: c_int,
+) -> c_int {
+    let bc = raw::RedisModule_GetBlockedClientHandle.unwrap()(ctx);
+    let pending = remove_pending(bc);
+
+    let ctx = Context::new(ctx);
+    match pending {
+        Some(mut pending) => {
+            ctx.log_warning(format!("dkann {} shards timeout", pending.waiting).as_str());
+            pending
+                .errors
+                .push(format!("{} shards timeout", pending.waiting));
+            let reply = DkannReply::new(pending.matches, pending.errors, pending.k);
+            raw::RedisModule_UnblockClient.unwrap()(bc, ptr::null_mut());
+            ctx.reply(Ok(reply.into())) as c_int
+        }
+        None => ctx.reply(Err(RedisError::Str("dkann timeout"))) as c_int,
+    }
+}
+
+// the disconnected client isn't replied, the pending search is dropped and the handle is freed
+unsafe extern "C" fn dkann_disconnected(
+    _ctx: *mut raw::RedisModuleCtx,
+    bc: *mut raw::RedisModuleBlockedClient,
+) {
+    if remove_pending(bc).is_some() {
+        raw::RedisModule_UnblockClient.unwrap()(bc, ptr::null_mut());
+    }
+}
+
+unsafe extern "C" fn free_dkann_reply(_ctx: *mut raw::RedisModuleCtx, privdata: *mut c_void) {
+    if !privdata.is_null() {
+        drop(Box::from_raw(privdata as *mut DkannReply));
+    }
+}
+
+unsafe extern "C" fn on_cluster_message(
+    ctx: *mut raw::RedisModuleCtx,
+    sender_id: *const c_char,
+    msg_type: u8,
+    payload: *const c_uchar,
+    len: u32,
+) {
+    let ctx = Context::new(ctx);
+    let payload = std::slice::from_raw_parts(payload, len as usize);
+    let sender = match node_id(sender_id) {
+        Some(sender) => sender,
+        None => return,
+    };
+    let res = match msg_type {
+        MSG_DKANN_REQUEST => on_dkann_request(&ctx, &sender, payload),
+        MSG_DKANN_RESPONSE => on_dkann_response(payload),
+        _ => Ok(()),
+    };
+    if let Err(e) = res {
+        ctx.log_warning(
+            format!("cluster message {} from {:?} err {}", msg_type, sender, e).as_str(),
+        );
+    }
+}
+
+fn on_dkann_request(ctx: &Context, sender: &CString, payload: &[u8]) -> Result<(), String> {
+    let req: DkannRequest = serde_json::from_slice(payload).map_err(|e| e.to_string())?;
+    let res = unsafe { raw::RedisModule_SelectDb.unwrap()(ctx.ctx, req.db) };
+    let (matches, errors) = if res != raw::REDISMODULE_OK as c_int {
+        (Vec::new(), vec![format!("select db {} err", req.db)])
+    } else {
+        search_local(ctx, req.user.as_deref(), &req.pattern, req.k, &req.vector)
+    };
+
+    let resp = DkannResponse {
+        id: req.id,
+        matches,
+        errors,
+    };
+    let payload = serde_json::to_vec(&resp).map_err(|e| e.to_string())?;
+    if !send_message(ctx, sender, MSG_DKANN_RESPONSE, &payload) {
+        return Err(format!("send dkann response {} err", req.id));
+    }
+    Ok(())
+}
+
+fn on_dkann_response(payload: &[u8]) -> Result<(), String> {
+    let resp: DkannResponse = serde_json::from_slice(payload).map_err(|e| e.to_string())?;
+    let mut searches = PENDING_SEARCHES.lock().unwrap();
+    // the search maybe timeout
+    let search = match searches.get_mut(&resp.id) {
+        Some(search) => search,
+        None => return Ok(()),
+    };
+    search.matches.extend(resp.matches);
+    search.errors.extend(resp.errors);
+    search.waiting -= 1;
+    if search.waiting == 0 {
+        if let Some(search) = searches.remove(&resp.id) {
+            unblock(search);
+        }
+    }
+    Ok(())
+}
+
+// register_cluster_receivers
+// only registered in cluster mode
+pub fn register_cluster_receivers(ctx: &Context) {
+    if !is_cluster(ctx) {
+        return;
+    }
+    for msg_type in [MSG_DKANN_REQUEST, MSG_DKANN_RESPONSE] {
+        unsafe {
+            raw::RedisModule_RegisterClusterMessageReceiver.unwrap()(
+                ctx.ctx,
+                msg_type,
+                Some(on_cluster_message),
+            );
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use std::env;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::Mutex;
+
+use redis_module::{Context, RedisString};
+
+use modcore::config::{parse_bool, parse_i64};
+// configs of every module, e.g. usearch.search_threads
+pub use modcore::config::{
+    notify_events, on_search_threads_changed, search_threads, DEFAULT_SEARCH_THREADS,
+    MAX_SEARCH_THREADS, NOTIFY_EVENTS, SEARCH_THREADS,
+};
+
+pub static DEFAULT_RESERVE_CAP: i64 = 10;
+pub static MAX_RESERVE_CAP: i64 = 100_000;
+pub static DEFAULT_EF_SEARCH: i64 = 3;
+pub static MAX_EF_SEARCH: i64 = 100_000;
+
+lazy_static! {
+    // module configs: CONFIG SET/GET usearch.<name>, saved by CONFIG REWRITE.
+    // they are read on the main thread and workers, so they're atomics instead of gil guarded values.
+
+    // serialization file dir of the new indexes, empty is the redis working dir
+    pub static ref SERIALIZATION_FILE_PATH_DIR: Mutex<String> = Mutex::new(String::new());
+    // remove the serialization file when the index is deleted
+    pub static ref REMOVE_SERIALIZED_FILE: AtomicBool = AtomicBool::new(false);
+    // capacity reserved when the index is full
+    pub static ref RESERVE_CAP: AtomicI64 = AtomicI64::new(DEFAULT_RESERVE_CAP);
+    // expansion_search of the new indexes
+    pub static ref EF_SEARCH: AtomicI64 = AtomicI64::new(DEFAULT_EF_SEARCH);
+}
+
+pub fn serialization_file_path_dir() -> String {
+    let dir = SERIALIZATION_FILE_PATH_DIR.lock().unwrap().clone();
+    if !dir.is_empty() {
+        return dir;
+    }
+    env::current_dir()
+        .map(|d| d.to_string_lossy().to_string())
+        .unwrap_or_else(|_| ".".to_string())
+}
+
+pub fn remove_serialized_file() -> bool {
+    REMOVE_SERIALIZED_FILE.load(Ordering::Relaxed)
+}
+
+pub fn reserve_cap() -> usize {
+    RESERVE_CAP.load(Ordering::Relaxed) as usize
+}
+
+pub fn ef_search() -> usize {
+    EF_SEARCH.load(Ordering::Relaxed) as usize
+}
+
+// apply_module_args
+// module args are key value pairs, e.g. --loadmodule libredisxann_usearch.so reserve_cap 100,
+// see modcore::config::apply_module_args.
+// note: is_remove_serialized_file and index_reserve_cap are the old names of the configs.
+pub fn apply_module_args(ctx: &Context, args: &[RedisString]) -> Result<(), String> {
+    modcore::config::apply_module_args(ctx, args, |name, value| {
+        match name.to_lowercase().as_str() {
+            "serialization_file_path_dir" => {
+                *SERIALIZATION_FILE_PATH_DIR.lock().unwrap() = value.to_string();
+            }
+            "remove_serialized_file" | "is_remove_serialized_file" => {
+                REMOVE_SERIALIZED_FILE.store(parse_bool(name, value)?, Ordering::Relaxed);
+            }
+            "reserve_cap" | "index_reserve_cap" => {
+                let cap = parse_i64(name, value, 1, MAX_RESERVE_CAP)?;
+                RESERVE_CAP.store(cap, Ordering::Relaxed);
+            }
+            "ef_search" => {
+                let ef_search = parse_i64(name, value, 1, MAX_EF_SEARCH)?;
+                EF_SEARCH.store(ef_search, Ordering::Relaxed);
+            }
+            _ => return Ok(false),
+        }
+        Ok(true)
+    })
+}
@@ -0,0 +1,241 @@
+use std::collections::HashSet;
+use std::os::raw::{c_int, c_void};
+
+use redis_module::{raw, Context, NotifyEvent, RedisError, RedisString};
+
+use crate::types::*;
+use crate::{alias, config, registry, stats, storage};
+use crate::{get_selected_db, index_file_path};
+
+// notify_keyspace_event
+// fire the module keyspace event of a write cmd on the key, e.g. usearch.node.add,
+// subscribers need the d class of notify-keyspace-events, e.g. CONFIG SET notify-keyspace-events KEd,
+// config notify_events no disables them.
+pub fn notify_keyspace_event(ctx: &Context, event: &str, key_name: &str) {
+    if !config::notify_events() {
+        return;
+    }
+    ctx.notify_keyspace_event(NotifyEvent::MODULE, event, &ctx.create_string(key_name));
+}
+
+// on_keyspace_event
+// keep the index registry in sync with keyspace changes which aren't made by module cmds,
+// and the index identity (name, node names, serialization file path) follows the index key.
+pub fn on_keyspace_event(ctx: &Context, _event_type: NotifyEvent, event: &str, key: &[u8]) {
+    let db = get_selected_db(ctx);
+    let key_name = String::from_utf8_lossy(key).to_string();
+    match event {
+        // key is removed from db
+        "del" | "expired" | "evicted" => {
+            if !registry::remove(db, &key_name) {
+                return;
+            }
+            alias::remove_index(db, &key_name);
+            if config::remove_serialized_file() {
+                let path = storage::file_path(db, &key_name);
+                if let Err(e) = storage::remove(&path) {
+                    ctx.log_debug(format!("path {} remove err {}", path, e).as_str());
+                }
+            }
+        }
+        // aliases don't follow the index key
+        "rename_from" | "move_from" => {
+            if registry::remove(db, &key_name) {
+                alias::remove_index(db, &key_name);
+            }
+        }
+        // index value is moved to the key, which maybe in another db
+        "rename_to" | "move_to" | "restore" => {
+            let rkey = ctx.open_key_writable(&RedisString::create_from_slice(ctx.ctx, key));
+            match rkey.get_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE) {
+                Ok(Some(index)) => {
+                    ctx.log_debug(
+                        format!(
+                            "{} Index: {} to {} in db {}",
+                            event, index.name, key_name, db
+                        )
+                        .as_str(),
+                    );
+                    index.rebind(&key_name, index_file_path(db, &key_name));
+                    registry::add(db, &key_name);
+                }
+                _ => {
+                    registry::remove(db, &key_name);
+                }
+            }
+        }
+        // key value is set, check whether it is an index
+        "copy_to" => {
+            let rkey = ctx.open_key(&RedisString::create_from_slice(ctx.ctx, key));
+            match rkey.get_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE) {
+                Ok(Some(_)) => registry::add(db, &key_name),
+                _ => {
+                    registry::remove(db, &key_name);
+                }
+            }
+        }
+        // the loaded index maybe saved by an older module outside the managed storage dir,
+        // it's saved in the managed dir from the next save.
+        "loaded" => {
+            let rkey = ctx.open_key_writable(&RedisString::create_from_slice(ctx.ctx, key));
+            match rkey.get_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE) {
+                Ok(Some(index)) => {
+                    index.rebind(&key_name, index_file_path(db, &key_name));
+                    registry::add(db, &key_name);
+                }
+                _ => {
+                    registry::remove(db, &key_name);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+unsafe extern "C" fn on_flush_event(
+    _ctx: *mut raw::RedisModuleCtx,
+    _eid: raw::RedisModuleEvent,
+    subevent: u64,
+    data: *mut c_void,
+) {
+    if subevent != raw::REDISMODULE_SUBEVENT_FLUSHDB_END as u64 {
+        return;
+    }
+    let fi = &*(data as *const raw::RedisModuleFlushInfoV1);
+    registry::flush(fi.dbnum);
+    alias::flush(fi.dbnum);
+}
+
+unsafe extern "C" fn on_swapdb_event(
+    ctx: *mut raw::RedisModuleCtx,
+    _eid: raw::RedisModuleEvent,
+    _subevent: u64,
+    data: *mut c_void,
+) {
+    let si = &*(data as *const raw::RedisModuleSwapDbInfoV1);
+    registry::swap(si.dbnum_first, si.dbnum_second);
+    alias::swap(si.dbnum_first, si.dbnum_second);
+
+    // serialization file path contains the db
+    let ctx = Context::new(ctx);
+    for db in [si.dbnum_first, si.dbnum_second] {
+        if let Err(e) = rebind_db(&ctx, db) {
+            ctx.log_warning(format!("swapdb rebind db {} err {}", db, e).as_str());
+        }
+    }
+}
+
+fn select_db(ctx: &Context, db: i32) -> Result<(), RedisError> {
+    let res = unsafe { raw::RedisModule_SelectDb.unwrap()(ctx.ctx, db) };
+    if res != raw::REDISMODULE_OK as c_int {
+        return Err(RedisError::String(format!("select db {} err", db)));
+    }
+    Ok(())
+}
+
+// rebind indexes in db after the db is swapped
+fn rebind_db(ctx: &Context, db: i32) -> Result<(), RedisError> {
+    select_db(ctx, db)?;
+    for name in registry::list(db) {
+        let rkey = ctx.open_key_writable(&ctx.create_string(name.clone()));
+        if let Some(index) = rkey.get_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE)? {
+            index.rebind(&name, index_file_path(db, &name));
+        }
+    }
+    Ok(())
+}
+
+// serialization file paths of the registered indexes
+fn live_file_paths(ctx: &Context) -> Result<HashSet<String>, RedisError> {
+    let mut paths = HashSet::new();
+    for (db, name) in registry::all() {
+        select_db(ctx, db)?;
+        let rkey = ctx.open_key(&ctx.create_string(name));
+        if let Some(index) = rkey.get_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE)? {
+            paths.insert(index.serialization_file_path.clone());
+        }
+    }
+    Ok(paths)
+}
+
+unsafe extern "C" fn on_loading_event(
+    ctx: *mut raw::RedisModuleCtx,
+    _eid: raw::RedisModuleEvent,
+    subevent: u64,
+    _data: *mut c_void,
+) {
+    // the dataset is replaced on rdb/aof loading and replica full sync,
+    // index keys are registered again by loaded events, aliases are loaded from rdb aux data or aof cmds.
+    if subevent == raw::REDISMODULE_SUBEVENT_LOADING_RDB_START as u64
+        || subevent == raw::REDISMODULE_SUBEVENT_LOADING_AOF_START as u64
+        || subevent == raw::REDISMODULE_SUBEVENT_LOADING_REPL_START as u64
+    {
+        stats::load_started();
+        registry::flush(-1);
+        alias::flush(-1);
+    } else if subevent == raw::REDISMODULE_SUBEVENT_LOADING_ENDED as u64 {
+        stats::load_ended();
+        // the loaded dataset is the persisted one, files of the other indexes are orphans
+        let ctx = Context::new(ctx);
+        match live_file_paths(&ctx) {
+            Ok(paths) => {
+                let removed = storage::gc(&paths);
+                ctx.log_notice(format!("gc removed {} orphaned index files", removed).as_str());
+            }
+            Err(e) => ctx.log_warning(format!("gc index files err {}", e).as_str()),
+        }
+    } else if subevent == raw::REDISMODULE_SUBEVENT_LOADING_FAILED as u64 {
+        stats::load_ended();
+    }
+}
+
+unsafe extern "C" fn on_persistence_event(
+    _ctx: *mut raw::RedisModuleCtx,
+    _eid: raw::RedisModuleEvent,
+    subevent: u64,
+    _data: *mut c_void,
+) {
+    // background saves fire the events in the child, only saves on the main thread are timed here,
+    // e.g. SAVE and the shutdown save, redis reports bgsave durations in INFO persistence.
+    if subevent == raw::REDISMODULE_SUBEVENT_PERSISTENCE_RDB_START as u64
+        || subevent == raw::REDISMODULE_SUBEVENT_PERSISTENCE_AOF_START as u64
+        || subevent == raw::REDISMODULE_SUBEVENT_PERSISTENCE_SYNC_RDB_START as u64
+        || subevent == raw::REDISMODULE_SUBEVENT_PERSISTENCE_SYNC_AOF_START as u64
+    {
+        stats::save_started();
+    } else if subevent == raw::REDISMODULE_SUBEVENT_PERSISTENCE_ENDED as u64
+        || subevent == raw::REDISMODULE_SUBEVENT_PERSISTENCE_FAILED as u64
+    {
+        stats::save_ended();
+    }
+}
+
+// subscribe_server_events
+// FLUSHDB/FLUSHALL, SWAPDB and loading don't fire keyspace events for each key,
+// loading and persistence are timed for INFO.
+pub fn subscribe_server_events(ctx: &Context) -> Result<(), RedisError> {
+    let events: [(u32, raw::RedisModuleEventCallback); 4] = [
+        (raw::REDISMODULE_EVENT_FLUSHDB, Some(on_flush_event)),
+        (raw::REDISMODULE_EVENT_SWAPDB, Some(on_swapdb_event)),
+        (raw::REDISMODULE_EVENT_LOADING, Some(on_loading_event)),
+        (
+            raw::REDISMODULE_EVENT_PERSISTENCE,
+            Some(on_persistence_event),
+        ),
+    ];
+    for (id, callback) in events {
+        let event = raw::RedisModuleEvent {
+            id: id as u64,
+            dataver: 1,
+        };
+        let res =
+            unsafe { raw::RedisModule_SubscribeToServerEvent.unwrap()(ctx.ctx, event, callback) };
+        if res != raw::REDISMODULE_OK as c_int {
+            return Err(RedisError::String(format!(
+                "subscribe server event {} err",
+                id
+            )));
+        }
+    }
+    Ok(())
+}
@@ -3,87 +3,205 @@
 #[macro_use]
 extern crate lazy_static;
 
-use std::collections::hash_map::Entry;
-use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
-use std::{env, fs};
-
+use std::os::raw::c_int;
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::Instant;
+
+mod alias;
+mod cluster;
+mod config;
+mod events;
+mod rebuild;
+mod storage;
 //#[allow(dead_code, unused_variables, unused_mut)]
 mod types;
+use modcore::acl::check_key_acl;
+use modcore::errors::ErrorCode;
+use modcore::lock::{self, write_lock};
+use modcore::{errors, info, pool, registry, stats};
 use types::*;
 
+use redis_module::configuration::ConfigurationFlags;
 use redis_module::{
-    raw, redis_module, Context, NextArg, RedisError, RedisResult, RedisString, RedisValue, Status,
+    raw, redis_module, Context, ContextFlags, InfoContext, NextArg, RedisError, RedisResult,
+    RedisString, RedisValue, Status, ThreadSafeContext,
 };
 use sonyflake::Sonyflake;
 
 static PREFIX: &str = "usearch";
-static SUFFIX: &str = "idx";
-static MODULE_NAME: &str = "redisxann-usearch";
-static ARG_PATH_DIR_NAME: &str = "serialization_file_path_dir";
-static ARG_REMOVE_SERIALIZED_FILE: &str = "is_remove_serialized_file";
-static ARG_USEARCH_INDEX_RESERVE_CAP: &str = "index_reserve_cap";
+// module configs are prefixed by the module name, e.g. usearch.reserve_cap
+static MODULE_NAME: &str = "usearch";
 static USEARCH_INDEX_RESERVE_CAP: usize = 10;
-static MAX_USEARCH_INDEX_RESERVE_CAP: usize = 100_000;
+static SCAN_DEFAULT_COUNT: usize = 10;
+static MAX_INDEX_PARTITIONS: usize = 64;
 
 lazy_static! {
     // note: usearch::Index it is already thread-safe for concurrent additions from different threads but can't run search in parallel with that maybe in the next v3 release
     //static ref INDICES: RwLock<HashMap<String, Index>> = RwLock::new(HashMap::new());
 
-    // just use init load args, then read it's args for cmd,,
-    static ref MODULE_ARGS_MAP: RwLock<HashMap<String, String>> = {
-        let mut m = HashMap::new();
-        m.insert(ARG_PATH_DIR_NAME.to_string(), env::current_dir().unwrap().to_string_lossy().to_string());
-        RwLock::new(m)
-    };
-
     // use id generator, need hashmap to save k/v (name/id)
     static ref ID_GENER: Sonyflake = Sonyflake::new().unwrap();
 
     // or use hash funciton: https://clickhouse.com/docs/en/sql-reference/functions/hash-functions
 }
 
+fn get_selected_db(ctx: &Context) -> i32 {
+    unsafe { raw::RedisModule_GetSelectedDb.unwrap()(ctx.get_raw()) }
+}
+
+// check_index_key_name
+// the cmd key arg is the index key name, e.g. usearch.idx0,
+// so acl key patterns (e.g. ~usearch.tenantA*) and cluster slots apply to the index key.
+fn check_index_key_name(name: &str) -> Result<(), RedisError> {
+    match name.strip_prefix(PREFIX).and_then(|n| n.strip_prefix('.')) {
+        Some(n) if !n.is_empty() => Ok(()),
+        _ => Err(errors::bad_param(format!(
+            "index key {} must be {}.<name>",
+            name, PREFIX
+        ))),
+    }
+}
+
+// index_key_name
+// index key name of the cmd key arg, aliases are resolved to the index,
+// the acl of the resolved index is checked as it isn't the cmd key arg.
+fn index_key_name(ctx: &Context, name: &str, write: bool) -> Result<String, RedisError> {
+    check_index_key_name(name)?;
+    let index_name = alias::resolve(get_selected_db(ctx), name.to_string());
+    if index_name != name {
+        check_key_acl(ctx, &ctx.create_string(index_name.clone()), write)?;
+    }
+    Ok(index_name)
+}
+
+// parse_vector
+// the vector arg is comma separated floats, its dimensions must match the index
+fn parse_vector(index_redis: &IndexRedis, vector_arg: &str) -> Result<Vec<f64>, RedisError> {
+    let vector_str: Vec<&str> = vector_arg.split(",").collect();
+    let dimensions = index_redis.index_opts.dimensions;
+    if vector_str.len() != dimensions {
+        return Err(errors::error(
+            ErrorCode::DimMismatch,
+            format!(
+                "vector dimensions {} does not match Index: {} dimensions {}{}",
+                vector_str.len(),
+                index_redis.name,
+                dimensions,
+                match &index_redis.index_opts.model {
+                    Some(model) => format!(" of model {}", model),
+                    None => "".to_string(),
+                }
+            ),
+        ));
+    }
+    let mut vector: Vec<f64> = Vec::with_capacity(dimensions);
+    for item in vector_str.iter() {
+        let data = item
+            .parse::<f64>()
+            .map_err(|e| errors::bad_param(format!("vector item {} err {}", item, e)))?;
+        vector.push(data);
+    }
+    Ok(vector)
+}
+
+// check_model
+// the MODEL assertion of writers and queries must match the index model
+fn check_model(index_redis: &IndexRedis, model: Option<&String>) -> Result<(), RedisError> {
+    match (model, &index_redis.index_opts.model) {
+        (None, _) => Ok(()),
+        (Some(model), Some(index_model)) if model == index_model => Ok(()),
+        (Some(model), index_model) => Err(errors::bad_param(format!(
+            "model {} does not match Index: {} model {}",
+            model,
+            index_redis.name,
+            index_model.as_deref().unwrap_or("(none)")
+        ))),
+    }
+}
+
+// serialization file path of the index key name in db, assigned in the managed storage dir
+pub(crate) fn index_file_path(db: i32, name: &str) -> String {
+    storage::assign(db, name)
+}
+
 // create_index
-// cmd: usearch.index.create indexName [algo_param_key algo_param_value]
-// cmd eg: usearch.index.create idx0 dim 3 m 10 efcon 12 metric ip quantization f32
+// cmd: usearch.index.create indexName [algo_param_key algo_param_value] [PARTITIONS n] [MODEL name] [VERSION v]
+// cmd eg: usearch.index.create usearch.idx0 dim 3 m 10 efcon 12 metric ip quantization f32 partitions 4 model clip version 1
 // return "OK" or error
+// note: PARTITIONS n splits vectors across n sub indexes by vector id hash, searched one by one and merged.
+// MODEL/VERSION tag the embedding model which produces the vectors.
 fn create_index(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     ctx.auto_memory();
-    if args.len() < 12 {
+    if args.len() < 12 || args.len() % 2 != 0 {
         return Err(RedisError::WrongArity);
     }
 
-    let db = unsafe { raw::RedisModule_GetSelectedDb.unwrap()(ctx.get_raw()) };
+    let db = get_selected_db(ctx);
 
     let mut args = args.into_iter().skip(1);
-    let name = format!("{}.{}", PREFIX, args.next_str()?);
+    let name = args.next_string()?;
+    check_index_key_name(&name)?;
     let index_name = ctx.create_string(name.clone());
+    if alias::contains(db, &name) {
+        return Err(RedisError::String(format!("Index: {} is an alias", name)));
+    }
 
-    if args.next_string()?.to_lowercase() != "dim" {
-        return Err(RedisError::WrongArity);
+    let param = args.next_string()?;
+    if param.to_lowercase() != "dim" {
+        return Err(errors::bad_param(format!("need dim param but {}", param)));
     }
     let dim = args.next_u64()? as usize;
 
-    if args.next_string()?.to_lowercase() != "m" {
-        return Err(RedisError::WrongArity);
+    let param = args.next_string()?;
+    if param.to_lowercase() != "m" {
+        return Err(errors::bad_param(format!("need m param but {}", param)));
     }
     let m = args.next_u64()? as usize;
 
-    if args.next_string()?.to_lowercase() != "efcon" {
-        return Err(RedisError::WrongArity);
+    let param = args.next_string()?;
+    if param.to_lowercase() != "efcon" {
+        return Err(errors::bad_param(format!("need efcon param but {}", param)));
     }
     let ef_construction = args.next_u64()? as usize;
 
-    if args.next_string()?.to_lowercase() != "metric" {
-        return Err(RedisError::WrongArity);
+    let param = args.next_string()?;
+    if param.to_lowercase() != "metric" {
+        return Err(errors::bad_param(format!(
+            "need metric param but {}",
+            param
+        )));
     }
     let metric = args.next_string()?.to_lowercase();
 
-    if args.next_string()?.to_lowercase() != "quantization" {
-        return Err(RedisError::WrongArity);
+    let param = args.next_string()?;
+    if param.to_lowercase() != "quantization" {
+        return Err(errors::bad_param(format!(
+            "need quantization param but {}",
+            param
+        )));
     }
     let quantization = args.next_string()?.to_lowercase();
 
+    let mut partitions = 1;
+    let mut model = None;
+    let mut version = None;
+    while let Ok(arg) = args.next_string() {
+        match arg.to_lowercase().as_str() {
+            "partitions" => {
+                partitions = args.next_u64()? as usize;
+                if partitions == 0 || partitions > MAX_INDEX_PARTITIONS {
+                    return Err(errors::bad_param(format!(
+                        "PARTITIONS must be in [1, {}]",
+                        MAX_INDEX_PARTITIONS
+                    )));
+                }
+            }
+            "model" => model = Some(args.next_string()?),
+            "version" => version = Some(args.next_string()?),
+            _ => return Err(errors::bad_param(format!("unknown param {}", arg))),
+        }
+    }
+
     // get index redisType value
     let key = ctx.open_key_writable(&index_name);
     match key.get_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE)? {
@@ -98,45 +216,212 @@ fn create_index(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
             opts.dimensions = dim;
             opts.connectivity = m;
             opts.expansion_add = ef_construction;
+            opts.expansion_search = config::ef_search();
             opts.metric = metric.into();
             opts.quantization = quantization.into();
+            opts.model = model;
+            opts.version = version;
 
             // create index
             let mut redis_idx = IndexRedis::default();
             redis_idx.name = name.clone();
             redis_idx.index_opts = opts.clone();
-            let idx = usearch::Index::new(&opts.into()).unwrap();
-            let res = idx.reserve(USEARCH_INDEX_RESERVE_CAP);
-            if res.is_err() {
-                return Err(RedisError::String(format!(
-                    "new Index {} reserve cap err {}",
-                    name,
-                    res.err().unwrap()
-                )));
+            for i in 0..partitions {
+                let idx = new_usearch_index(&opts, USEARCH_INDEX_RESERVE_CAP).map_err(|e| {
+                    RedisError::String(format!("new Index {} reserve cap err {}", name, e))
+                })?;
+                match i {
+                    0 => redis_idx.index = Some(Arc::new(idx)),
+                    _ => redis_idx.partitions.push(Arc::new(idx)),
+                }
             }
-            redis_idx.serialization_file_path = MODULE_ARGS_MAP
-                .read()
-                .unwrap()
-                .get(ARG_PATH_DIR_NAME)
-                .unwrap()
-                .to_string();
-            redis_idx
-                .serialization_file_path
-                .push_str(format!("/{}.{}.{}", db, name, SUFFIX).as_str());
-            redis_idx.index = Some(Arc::new(idx));
+            redis_idx.serialization_file_path = index_file_path(db, &name);
 
             // set index redisType value
             ctx.log_debug(format!("create Usearch Index {:?}", redis_idx).as_str());
             key.set_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE, redis_idx.into())?;
+            registry::add(db, &name);
         }
     }
 
+    events::notify_keyspace_event(ctx, &format!("{}.index.create", PREFIX), &name);
+    ctx.replicate_verbatim();
     Ok("OK".into())
 }
 
+// rebuild_index
+// cmd: usearch.index.rebuild srcIndexName dstIndexName [algo_param_key algo_param_value]
+// cmd eg: usearch.index.rebuild usearch.idx0 usearch.idx0 m 16 efcon 64 quantization f16 partitions 2
+// return "OK" or error
+// note: the dst index is built from the src vectors in background with the src options
+// overridden by the given options, then swapped into the dst index key atomically,
+// dst can be the src index to retune it without downtime.
+fn rebuild_index(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    ctx.auto_memory();
+    if args.len() < 3 || args.len() % 2 != 1 {
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+    let src = index_key_name(ctx, args.next_str()?, false)?;
+    let dst = index_key_name(ctx, args.next_str()?, true)?;
+    let src_name = ctx.create_string(src.clone());
+    let dst_name = ctx.create_string(dst.clone());
+
+    let src_key = ctx.open_key(&src_name);
+    let src_index = src_key
+        .get_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE)?
+        .ok_or_else(|| errors::no_index(&src))?;
+    if src != dst && !ctx.open_key(&dst_name).is_empty() {
+        return Err(RedisError::String(format!("Index: {} already exists", dst)));
+    }
+
+    let mut opts = src_index.index_opts.clone();
+    let mut partitions = src_index.partitions.len() + 1;
+    while let Ok(param) = args.next_string() {
+        match param.to_lowercase().as_str() {
+            "m" => opts.connectivity = args.next_u64()? as usize,
+            "efcon" => opts.expansion_add = args.next_u64()? as usize,
+            "metric" => opts.metric = args.next_string()?.to_lowercase().into(),
+            "quantization" => opts.quantization = args.next_string()?.to_lowercase().into(),
+            "model" => opts.model = Some(args.next_string()?),
+            "version" => opts.version = Some(args.next_string()?),
+            "partitions" => {
+                partitions = args.next_u64()? as usize;
+                if partitions == 0 || partitions > MAX_INDEX_PARTITIONS {
+                    return Err(errors::bad_param(format!(
+                        "PARTITIONS must be in [1, {}]",
+                        MAX_INDEX_PARTITIONS
+                    )));
+                }
+            }
+            _ => return Err(errors::bad_param(format!("unknown param {}", param))),
+        }
+    }
+
+    // the swap replicates the rebuilt index by usearch.index.swap, not the rebuild
+    rebuild::rebuild(ctx, src_index, dst, opts, partitions)?;
+
+    Ok("OK".into())
+}
+
+// swap_index
+// cmd: usearch.index.swap dstIndexName optsJson nodeIdNameJson nodeNameIdJson vectorIdsJson partitionBuffer [partitionBuffer ...]
+// return "OK" or error
+// note: the swap of usearch.index.rebuild replicates the rebuilt index to replicas and the aof by it,
+// clients can't call it.
+fn swap_index(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    ctx.auto_memory();
+    if !ctx
+        .get_flags()
+        .intersects(ContextFlags::REPLICATED | ContextFlags::LOADING)
+    {
+        return Err(RedisError::String(format!(
+            "ERR {}.index.swap is only replicated by {}.index.rebuild",
+            PREFIX, PREFIX
+        )));
+    }
+    if args.len() < 7 {
+        return Err(RedisError::WrongArity);
+    }
+
+    let db = get_selected_db(ctx);
+    let index = rebuild::swapped_index(db, &args[1..]).map_err(RedisError::String)?;
+    let name = index.name.clone();
+    let index_name = ctx.create_string(name.clone());
+    ctx.open_key_writable(&index_name)
+        .set_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE, index)?;
+    registry::add(db, &name);
+
+    events::notify_keyspace_event(ctx, &format!("{}.index.rebuild", PREFIX), &name);
+    ctx.replicate_verbatim();
+    Ok("OK".into())
+}
+
+// set_alias
+// cmd: usearch.alias.set alias indexName
+// cmd eg: usearch.alias.set usearch.emb usearch.emb_v2
+// return "OK" or error
+// note: the alias is switched to the index atomically, cmds on the alias use the index.
+fn set_alias(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    ctx.auto_memory();
+    if args.len() != 3 {
+        return Err(RedisError::WrongArity);
+    }
+
+    let db = get_selected_db(ctx);
+    let mut args = args.into_iter().skip(1);
+    let alias_name = args.next_string()?;
+    check_index_key_name(&alias_name)?;
+    let name = index_key_name(ctx, args.next_str()?, false)?;
+    let index_name = ctx.create_string(name.clone());
+
+    ctx.open_key(&index_name)
+        .get_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE)?
+        .ok_or_else(|| errors::no_index(&name))?;
+    // the alias can't shadow a key
+    if !ctx
+        .open_key(&ctx.create_string(alias_name.clone()))
+        .is_empty()
+    {
+        return Err(RedisError::String(format!(
+            "key: {} already exists",
+            alias_name
+        )));
+    }
+
+    alias::set(db, &alias_name, &name);
+    events::notify_keyspace_event(ctx, &format!("{}.alias.set", PREFIX), &alias_name);
+    ctx.replicate_verbatim();
+    Ok("OK".into())
+}
+
+// del_alias
+// cmd: usearch.alias.del alias
+// cmd eg: usearch.alias.del usearch.emb
+// return 1 if the alias is deleted, 0 if it doesn't exist
+fn del_alias(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    ctx.auto_memory();
+    if args.len() != 2 {
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+    let alias_name = args.next_string()?;
+
+    let n = alias::remove(get_selected_db(ctx), &alias_name) as usize;
+    if n > 0 {
+        events::notify_keyspace_event(ctx, &format!("{}.alias.del", PREFIX), &alias_name);
+        ctx.replicate_verbatim();
+    }
+    Ok(n.into())
+}
+
+// list_alias
+// cmd: usearch.alias.list
+// cmd eg: usearch.alias.list
+// return [alias, indexName] pairs in the current db
+fn list_alias(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    ctx.auto_memory();
+    if args.len() != 1 {
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut reply: Vec<RedisValue> = Vec::new();
+    for (alias_name, name) in alias::list(get_selected_db(ctx)) {
+        // only list the aliases which the user can read
+        if check_key_acl(ctx, &ctx.create_string(alias_name.clone()), false).is_err() {
+            continue;
+        }
+        let pair: Vec<RedisValue> = vec![alias_name.into(), name.into()];
+        reply.push(pair.into());
+    }
+    Ok(reply.into())
+}
+
 // get_index
 // cmd: usearch.index.get indexName
-// cmd eg: usearch.index.get idx0
+// cmd eg: usearch.index.get usearch.idx0
 // return indexInfo or error
 fn get_index(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     ctx.auto_memory();
@@ -145,21 +430,21 @@ fn get_index(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     }
 
     let mut args = args.into_iter().skip(1);
-    let name = format!("{}.{}", PREFIX, args.next_str()?);
+    let name = index_key_name(ctx, args.next_str()?, false)?;
 
     // get index redisType value
     let index_name = ctx.create_string(name.clone());
     let key = ctx.open_key(&index_name);
     let index_redis = key
         .get_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE)?
-        .ok_or_else(|| RedisError::String(format!("Index: {} does not exist", name)))?;
+        .ok_or_else(|| errors::no_index(&name))?;
 
     Ok(index_redis.clone().into())
 }
 
 // del_index
 // cmd: usearch.index.del indexName
-// cmd eg: usearch.index.del idx0
+// cmd eg: usearch.index.del usearch.idx0
 // return 1 or error
 fn del_index(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     ctx.auto_memory();
@@ -169,24 +454,19 @@ fn del_index(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     }
 
     let mut args = args.into_iter().skip(1);
-    let name = format!("{}.{}", PREFIX, args.next_str()?);
+    let name = index_key_name(ctx, args.next_str()?, true)?;
 
     // get index redisType value
     let index_name = ctx.create_string(name.clone());
     let key = ctx.open_key_writable(&index_name);
     let index_redis = key
         .get_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE)?
-        .ok_or_else(|| RedisError::String(format!("Index: {} does not exist", name)))?;
+        .ok_or_else(|| errors::no_index(&name))?;
 
     // remove serialized index file
-    let is_remove = MODULE_ARGS_MAP
-        .read()
-        .unwrap()
-        .get(ARG_REMOVE_SERIALIZED_FILE)
-        .is_some();
-    if is_remove {
+    if config::remove_serialized_file() {
         // don't return
-        let res = fs::remove_file(index_redis.serialization_file_path.to_string());
+        let res = storage::remove(&index_redis.serialization_file_path);
         if res.is_err() {
             ctx.log_debug(
                 format!(
@@ -199,47 +479,92 @@ fn del_index(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
         }
     }
 
-    // delete usearch index
-    let res = index_redis.index.clone().unwrap().reset();
-    if res.is_err() {
-        return Err(RedisError::String(format!(
-            "Index: {} delete err {}",
-            name,
-            res.err().unwrap()
-        )));
-    }
+    // finally unlink redisType value, a big index is freed in the redis lazyfree thread,
+    // searches on workers hold the partitions until they are done.
+    key.unlink()?;
+    registry::remove(get_selected_db(ctx), &name);
+    alias::remove_index(get_selected_db(ctx), &name);
+
+    events::notify_keyspace_event(ctx, &format!("{}.index.del", PREFIX), &name);
+    ctx.replicate_verbatim();
+    Ok(1_usize.into())
+}
 
-    // clear node map
-    index_redis.node_id_name_map.clear();
-    index_redis.node_name_id_map.clear();
+// list_index
+// cmd: usearch.index.list
+// cmd eg: usearch.index.list
+// return index summary infos in the current db
+fn list_index(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    ctx.auto_memory();
 
-    // finally delete redisType value
-    key.delete()?;
+    if args.len() != 1 {
+        return Err(RedisError::WrongArity);
+    }
 
-    Ok(1_usize.into())
+    let db = get_selected_db(ctx);
+    let mut reply: Vec<RedisValue> = Vec::new();
+    for name in registry::list(db) {
+        let index_name = ctx.create_string(name.clone());
+        // only list the indexes which the user can read
+        if check_key_acl(ctx, &index_name, false).is_err() {
+            continue;
+        }
+        let key = ctx.open_key(&index_name);
+        let index_redis = match key.get_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE) {
+            Ok(Some(index_redis)) => index_redis,
+            _ => {
+                ctx.log_warning(format!("registry Index: {} does not exist", name).as_str());
+                registry::remove(db, &name);
+                continue;
+            }
+        };
+        let mut info: Vec<RedisValue> = Vec::new();
+        info.push("name".into());
+        info.push(name.into());
+        info.push("index_size".into());
+        info.push(index_redis.size().into());
+        info.push("dimensions".into());
+        info.push(index_redis.index_opts.dimensions.into());
+        info.push("metric".into());
+        info.push(format!("{:?}", index_redis.index_opts.metric).into());
+        info.push("quantization".into());
+        info.push(format!("{:?}", index_redis.index_opts.quantization).into());
+        info.push("index_mem_usage".into());
+        info.push(index_redis.memory_usage().into());
+        info.push("mem_usage".into());
+        info.push(index_redis.mem_usage(0).total().into());
+        reply.push(info.into());
+    }
+
+    Ok(reply.into())
 }
 
 // add_node
-// cmd: usearch.node.add indexName nodeName dataVector
-// cmd eg: usearch.node.add idx0 n1 0.6,0.1,0.1
+// cmd: usearch.node.add indexName nodeName dataVector [id vectorId] [MODEL name]
+// cmd eg: usearch.node.add usearch.idx0 n1 0.6,0.1,0.1 model clip
 // return "OK" or error
+// note: vector id is generated if not given, and replicated with the id,
+// so replicas and aof hold the same node id<>name map.
 fn add_node(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     ctx.auto_memory();
 
-    if args.len() <= 3 {
+    if args.len() < 4 || args.len() % 2 != 0 {
         return Err(RedisError::WrongArity);
     }
+    let _timer = stats::Timer::new(stats::Op::Insert);
 
     let mut args = args.into_iter().skip(1);
-    let name = format!("{}.{}", PREFIX, args.next_str()?);
-    let node_name = format!("{}.{}", name, args.next_str()?);
+    let short_name = args.next_string()?;
+    let short_node_name = args.next_string()?;
+    let name = index_key_name(ctx, &short_name, true)?;
+    let node_name = format!("{}.{}", name, short_node_name);
 
     // get index redisType value
     let index_name = ctx.create_string(name.clone());
     let index_key = ctx.open_key_writable(&index_name);
     let index_redis = index_key
         .get_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE)?
-        .ok_or_else(|| RedisError::String(format!("Index: {} does not exist", name)))?;
+        .ok_or_else(|| errors::no_index(&name))?;
 
     // check node name exist
     if index_redis.node_name_id_map.contains_key(&node_name) {
@@ -249,48 +574,51 @@ fn add_node(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
         )));
     }
 
-    let idx = index_redis.index.clone().unwrap();
-    let vector_str = args.next_string()?;
-    let vector_str: Vec<&str> = vector_str.split(",").collect();
-    if vector_str.len() != idx.dimensions() {
-        return Err(RedisError::WrongArity);
-    }
-    let mut vector: Vec<f64> = Vec::with_capacity(idx.dimensions());
-    for item in vector_str.iter() {
-        let data = item.parse::<f64>()?;
-        vector.push(data);
+    let vector_arg = args.next_string()?;
+    let mut vector_id = None;
+    let mut model = None;
+    while let Ok(arg) = args.next_string() {
+        match arg.to_lowercase().as_str() {
+            "id" => {
+                let id = args.next_u64()?;
+                if index_redis.contains_id(id as usize) {
+                    return Err(RedisError::String(format!(
+                        "vectorId: {} already exists",
+                        id
+                    )));
+                }
+                vector_id = Some(id);
+            }
+            "model" => model = Some(args.next_string()?),
+            _ => return Err(errors::bad_param(format!("unknown param {}", arg))),
+        }
     }
+    check_model(index_redis, model.as_ref())?;
+    let vector = parse_vector(index_redis, &vector_arg)?;
+    let vector_id = vector_id.unwrap_or_else(|| ID_GENER.next_id().unwrap());
+    let idx = index_redis.partition(vector_id);
 
     // add node to index
     // note: need check index cap and size, Manual reserve. maybe wait usearch v3 to support for multi threads case.
-    // just single thread, for redis cmd main thread to reserve Index capacity + USEARCH_INDEX_RESERVE_CAP
+    // just single thread, for redis cmd main thread to reserve Index capacity + usearch.reserve_cap
+    let _guard = write_lock(ctx, &name, &index_redis.search_lock)?;
     let curr_cap = idx.capacity();
-    let mut cap = curr_cap + USEARCH_INDEX_RESERVE_CAP;
-    let binding = MODULE_ARGS_MAP.read().unwrap();
-    let incr_cap = binding.get(ARG_USEARCH_INDEX_RESERVE_CAP);
-    if incr_cap.is_some() {
-        let mut incr = incr_cap
-            .unwrap()
-            .parse()
-            .unwrap_or_else(|_| USEARCH_INDEX_RESERVE_CAP);
-        if incr > MAX_USEARCH_INDEX_RESERVE_CAP {
-            incr = MAX_USEARCH_INDEX_RESERVE_CAP;
-        }
-        cap = curr_cap + incr;
-    }
+    let cap = curr_cap + config::reserve_cap();
     if idx.size() >= cap / 2 {
         let res = idx.reserve(cap);
         if res.is_err() {
-            return Err(RedisError::String(format!(
-                "Index: {} reserve cap {} err {}",
-                name,
-                cap,
-                res.err().unwrap()
-            )));
+            return Err(errors::error(
+                ErrorCode::Capacity,
+                format!(
+                    "Index: {} reserve cap {} err {}",
+                    name,
+                    cap,
+                    res.err().unwrap()
+                ),
+            ));
         }
         ctx.log_debug(format!("Index: {} reserve cap {} ok!", name, cap).as_str());
     }
-    let vector_id = ID_GENER.next_id().unwrap();
     let res = idx.add(vector_id, vector.as_ref());
     if res.is_err() {
         return Err(RedisError::String(format!(
@@ -308,6 +636,7 @@ fn add_node(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     index_redis
         .node_name_id_map
         .insert(node_name.clone(), vector_id as usize);
+    rebuild::changed(get_selected_db(ctx), &name, vector_id);
 
     ctx.log_debug(
         format!(
@@ -316,22 +645,38 @@ fn add_node(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
         )
         .as_str(),
     );
+
+    events::notify_keyspace_event(ctx, &format!("{}.node.add", PREFIX), &name);
+
+    // replicate the index name which the alias is resolved to
+    let vector_id = vector_id.to_string();
+    ctx.replicate(
+        format!("{}.node.add", PREFIX).as_str(),
+        &[
+            name.as_str(),
+            short_node_name.as_str(),
+            vector_arg.as_str(),
+            "id",
+            vector_id.as_str(),
+        ][..],
+    );
     Ok("OK".into())
 }
 
 // add_vector
-// cmd: usearch.node.add_id indexName vectorId dataVector
-// cmd eg: usearch.node.add_id idx0 123 0.6,0.1,0.1
+// cmd: usearch.node.add_id indexName vectorId dataVector [MODEL name]
+// cmd eg: usearch.node.add_id usearch.idx0 123 0.6,0.1,0.1 model clip
 // return "OK" or error
 fn add_vector(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     ctx.auto_memory();
 
-    if args.len() <= 3 {
+    if args.len() != 4 && args.len() != 6 {
         return Err(RedisError::WrongArity);
     }
+    let _timer = stats::Timer::new(stats::Op::Insert);
 
     let mut args = args.into_iter().skip(1);
-    let name = format!("{}.{}", PREFIX, args.next_str()?);
+    let name = index_key_name(ctx, args.next_str()?, true)?;
     let vector_id = args.next_u64()?;
 
     // get index redisType value
@@ -339,46 +684,36 @@ fn add_vector(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     let index_key = ctx.open_key_writable(&index_name);
     let index_redis = index_key
         .get_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE)?
-        .ok_or_else(|| RedisError::String(format!("Index: {} does not exist", name)))?;
-    let idx = index_redis.index.clone().unwrap();
+        .ok_or_else(|| errors::no_index(&name))?;
+    let idx = index_redis.partition(vector_id);
 
-    let vector_str = args.next_string()?;
-    let vector_str: Vec<&str> = vector_str.split(",").collect();
-    if vector_str.len() != idx.dimensions() {
-        return Err(RedisError::WrongArity);
-    }
-    let mut vector: Vec<f64> = Vec::with_capacity(idx.dimensions());
-    for item in vector_str.iter() {
-        let data = item.parse::<f64>()?;
-        vector.push(data);
+    let vector_arg = args.next_string()?;
+    if let Ok(arg) = args.next_string() {
+        if arg.to_lowercase() != "model" {
+            return Err(errors::bad_param(format!("need model param but {}", arg)));
+        }
+        check_model(index_redis, Some(&args.next_string()?))?;
     }
+    let vector = parse_vector(index_redis, &vector_arg)?;
 
     // add node to index
     // note: need check index cap and size, Manual reserve. maybe wait usearch v3 to support for multi threads case.
-    // just single thread, for redis cmd main thread to reserve Index capacity + USEARCH_INDEX_RESERVE_CAP
+    // just single thread, for redis cmd main thread to reserve Index capacity + usearch.reserve_cap
+    let _guard = write_lock(ctx, &name, &index_redis.search_lock)?;
     let curr_cap = idx.capacity();
-    let mut cap = curr_cap + USEARCH_INDEX_RESERVE_CAP;
-    let binding = MODULE_ARGS_MAP.read().unwrap();
-    let incr_cap = binding.get(ARG_USEARCH_INDEX_RESERVE_CAP);
-    if incr_cap.is_some() {
-        let mut incr = incr_cap
-            .unwrap()
-            .parse()
-            .unwrap_or_else(|_| USEARCH_INDEX_RESERVE_CAP);
-        if incr > MAX_USEARCH_INDEX_RESERVE_CAP {
-            incr = MAX_USEARCH_INDEX_RESERVE_CAP;
-        }
-        cap = curr_cap + incr;
-    }
+    let cap = curr_cap + config::reserve_cap();
     if idx.size() >= cap / 2 {
         let res = idx.reserve(cap);
         if res.is_err() {
-            return Err(RedisError::String(format!(
-                "Index: {} reserve cap {} err {}",
-                name,
-                cap,
-                res.err().unwrap()
-            )));
+            return Err(errors::error(
+                ErrorCode::Capacity,
+                format!(
+                    "Index: {} reserve cap {} err {}",
+                    name,
+                    cap,
+                    res.err().unwrap()
+                ),
+            ));
         }
     }
 
@@ -392,12 +727,23 @@ fn add_vector(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
         )));
     }
 
+    // keep the vector id for scan, the id maybe added by usearch.node.add with node name
+    if !index_redis
+        .node_id_name_map
+        .contains_key(&(vector_id as usize))
+    {
+        index_redis.vector_ids.insert(vector_id as usize);
+    }
+    rebuild::changed(get_selected_db(ctx), &name, vector_id);
+
+    events::notify_keyspace_event(ctx, &format!("{}.node.add_id", PREFIX), &name);
+    ctx.replicate_verbatim();
     Ok("OK".into())
 }
 
 // get_node
 // cmd: usearch.node.get indexName nodeName
-// cmd eg: usearch.node.get idx0 n1
+// cmd eg: usearch.node.get usearch.idx0 n1
 // return nodeInfo or error
 // todo: batch get
 fn get_node(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
@@ -408,7 +754,7 @@ fn get_node(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     }
 
     let mut args = args.into_iter().skip(1);
-    let name = format!("{}.{}", PREFIX, args.next_str()?);
+    let name = index_key_name(ctx, args.next_str()?, false)?;
     let node_name = format!("{}.{}", name, args.next_str()?);
 
     // get index redisType value
@@ -416,19 +762,16 @@ fn get_node(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     let index_key = ctx.open_key(&index_name);
     let index_redis = index_key
         .get_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE)?
-        .ok_or_else(|| RedisError::String(format!("Index: {} does not exist", name)))?;
+        .ok_or_else(|| errors::no_index(&name))?;
 
     // check node name exist
     if !index_redis.node_name_id_map.contains_key(&node_name) {
-        return Err(RedisError::String(format!(
-            "Node: {} does not exist",
-            node_name
-        )));
+        return Err(errors::no_node(node_name));
     }
     let id = *(index_redis.node_name_id_map.get(&node_name).unwrap()) as u64;
 
     // get node from usearch index
-    let idx = index_redis.index.clone().unwrap();
+    let idx = index_redis.partition(id);
     let mut vector = vec![0.0 as f64; index_redis.index_opts.dimensions];
     let n = idx.get(id, &mut vector)?;
     ctx.log_debug(
@@ -453,7 +796,7 @@ fn get_node(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
 
 // get_vector
 // cmd: usearch.node.get_id indexName vectorId
-// cmd eg: usearch.node.get_id idx0 123
+// cmd eg: usearch.node.get_id usearch.idx0 123
 // return nodeInfo or error
 // todo: batch get
 fn get_vector(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
@@ -464,7 +807,7 @@ fn get_vector(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     }
 
     let mut args = args.into_iter().skip(1);
-    let name = format!("{}.{}", PREFIX, args.next_str()?);
+    let name = index_key_name(ctx, args.next_str()?, false)?;
     let id = args.next_u64()?;
 
     // get index redisType value
@@ -472,10 +815,10 @@ fn get_vector(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     let index_key = ctx.open_key(&index_name);
     let index_redis = index_key
         .get_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE)?
-        .ok_or_else(|| RedisError::String(format!("Index: {} does not exist", name)))?;
+        .ok_or_else(|| errors::no_index(&name))?;
 
     // get node from usearch index
-    let idx = index_redis.index.clone().unwrap();
+    let idx = index_redis.partition(id);
     let mut vector = vec![0.0 as f64; index_redis.index_opts.dimensions];
     let n = idx.get(id, &mut vector)?;
     ctx.log_debug(
@@ -487,10 +830,10 @@ fn get_vector(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     );
 
     if n == 0 {
-        return Err(RedisError::String(format!(
-            "vectorId: {} does not exists",
-            id
-        )));
+        return Err(errors::error(
+            ErrorCode::NoNode,
+            format!("vectorId: {} does not exist", id),
+        ));
     }
 
     // reply
@@ -507,7 +850,7 @@ fn get_vector(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
 
 // delete_node
 // cmd: usearch.node.del indexName nodeName
-// cmd eg: usearch.node.del idx0 n1
+// cmd eg: usearch.node.del usearch.idx0 n1
 // return 1 or error
 // todo: batch del
 fn delete_node(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
@@ -516,9 +859,10 @@ fn delete_node(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     if args.len() != 3 {
         return Err(RedisError::WrongArity);
     }
+    let _timer = stats::Timer::new(stats::Op::Delete);
 
     let mut args = args.into_iter().skip(1);
-    let name = format!("{}.{}", PREFIX, args.next_str()?);
+    let name = index_key_name(ctx, args.next_str()?, true)?;
     let node_name = format!("{}.{}", name, args.next_str()?);
 
     // get index redisType value
@@ -526,20 +870,20 @@ fn delete_node(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     let index_key = ctx.open_key_writable(&index_name);
     let index_redis = index_key
         .get_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE)?
-        .ok_or_else(|| RedisError::String(format!("Index: {} does not exist", name)))?;
+        .ok_or_else(|| errors::no_index(&name))?;
 
     // check node name exist
     if !index_redis.node_name_id_map.contains_key(&node_name) {
-        return Err(RedisError::String(format!(
-            "Node: {} does not exists",
-            node_name
-        )));
+        return Err(errors::no_node(node_name));
     }
     let id = *(index_redis.node_name_id_map.get(&node_name).unwrap());
 
     // delete node from usearch index
-    let idx = index_redis.index.clone().unwrap();
-    let n = idx.remove(id as u64)?;
+    let idx = index_redis.partition(id as u64);
+    let n = {
+        let _guard = write_lock(ctx, &name, &index_redis.search_lock)?;
+        idx.remove(id as u64)?
+    };
     ctx.log_debug(
         format!(
             "Delete {} node {} id {} from Index: {:?}",
@@ -551,13 +895,16 @@ fn delete_node(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     // delte node map
     index_redis.node_id_name_map.remove(&id);
     index_redis.node_name_id_map.remove(&node_name);
+    rebuild::changed(get_selected_db(ctx), &name, id as u64);
 
+    events::notify_keyspace_event(ctx, &format!("{}.node.del", PREFIX), &name);
+    ctx.replicate_verbatim();
     Ok(n.into())
 }
 
 // delete_vector
 // cmd: usearch.node.del_id indexName vectorId
-// cmd eg: usearch.node.del_id idx0 123
+// cmd eg: usearch.node.del_id usearch.idx0 123
 // return 1 or error
 // todo: batch del
 fn delete_vector(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
@@ -566,9 +913,10 @@ fn delete_vector(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     if args.len() != 3 {
         return Err(RedisError::WrongArity);
     }
+    let _timer = stats::Timer::new(stats::Op::Delete);
 
     let mut args = args.into_iter().skip(1);
-    let name = format!("{}.{}", PREFIX, args.next_str()?);
+    let name = index_key_name(ctx, args.next_str()?, true)?;
     let id = args.next_u64()?;
 
     // get index redisType value
@@ -576,20 +924,112 @@ fn delete_vector(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
     let index_key = ctx.open_key_writable(&index_name);
     let index_redis = index_key
         .get_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE)?
-        .ok_or_else(|| RedisError::String(format!("Index: {} does not exist", name)))?;
+        .ok_or_else(|| errors::no_index(&name))?;
 
     // delete node from usearch index
-    let idx = index_redis.index.clone().unwrap();
-    let n = idx.remove(id)?;
+    let idx = index_redis.partition(id);
+    let n = {
+        let _guard = write_lock(ctx, &name, &index_redis.search_lock)?;
+        idx.remove(id)?
+    };
     ctx.log_debug(format!("Delete {} id {} from Index: {:?}", n, id, index_redis,).as_str());
 
+    // delete node map, the id maybe added by usearch.node.add with node name
+    if let Some(node_name) = index_redis.node_id_name_map.remove(&(id as usize)) {
+        index_redis.node_name_id_map.remove(&node_name);
+    }
+    index_redis.vector_ids.remove(&(id as usize));
+    rebuild::changed(get_selected_db(ctx), &name, id);
+
+    events::notify_keyspace_event(ctx, &format!("{}.node.del_id", PREFIX), &name);
+    ctx.replicate_verbatim();
     Ok(n.into())
 }
 
+// scan_node
+// cmd: usearch.node.scan indexName cursor [COUNT count] [WITHVECTORS]
+// cmd eg: usearch.node.scan usearch.idx0 0 COUNT 10 WITHVECTORS
+// return [nextCursor, [nodeInfo...]] or error, nextCursor 0 means the scan is complete.
+// note: the cursor is a node id, nodes are scanned in id order,
+// so the nodes which exist from the start to the end of a full iteration are returned exactly once.
+fn scan_node(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    ctx.auto_memory();
+
+    if args.len() < 3 || args.len() > 6 {
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+    let name = index_key_name(ctx, args.next_str()?, false)?;
+    let cursor = args.next_u64()? as usize;
+
+    let mut count = SCAN_DEFAULT_COUNT;
+    let mut with_vectors = false;
+    while let Ok(arg) = args.next_string() {
+        match arg.to_lowercase().as_str() {
+            "count" => {
+                count = args.next_u64()? as usize;
+                if count == 0 {
+                    return Err(errors::bad_param("COUNT must be greater than 0"));
+                }
+            }
+            "withvectors" => with_vectors = true,
+            _ => return Err(errors::bad_param(format!("unknown param {}", arg))),
+        }
+    }
+
+    // get index redisType value
+    let index_name = ctx.create_string(name.clone());
+    let index_key = ctx.open_key(&index_name);
+    let index_redis = index_key
+        .get_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE)?
+        .ok_or_else(|| errors::no_index(&name))?;
+
+    let mut nodes: Vec<RedisValue> = Vec::with_capacity(count);
+    let mut next_cursor = 0_usize;
+    for (id, node_name) in index_redis.nodes(cursor) {
+        if nodes.len() == count {
+            next_cursor = id;
+            break;
+        }
+
+        let mut node: Vec<RedisValue> = Vec::new();
+        node.push("id".into());
+        node.push(id.into());
+        node.push("name".into());
+        node.push(node_name.into());
+        if with_vectors {
+            let mut vector = vec![0.0 as f64; index_redis.index_opts.dimensions];
+            index_redis
+                .partition(id as u64)
+                .get(id as u64, &mut vector)?;
+            node.push("data".into());
+            node.push(vector.into());
+        }
+        nodes.push(node.into());
+    }
+    ctx.log_debug(
+        format!(
+            "Scan Index: {} cursor {} count {} next cursor {}",
+            name,
+            cursor,
+            nodes.len(),
+            next_cursor
+        )
+        .as_str(),
+    );
+
+    let mut reply: Vec<RedisValue> = Vec::new();
+    reply.push(next_cursor.to_string().into());
+    reply.push(nodes.into());
+
+    Ok(reply.into())
+}
+
 // search_kann
 // k-Approximate Nearest Neighbors (kANN) Search
 // cmd: usearch.search.kann indexName topK queryVector [filter_string] [param_key param_value]
-// cmd eg: usearch.search.kann idx0 6 0.0,0.0,0.0 "" ef_search 10
+// cmd eg: usearch.search.kann usearch.idx0 6 0.0,0.0,0.0 "" ef_search 10 model clip
 // return top K ANN node infos or error
 // todo: add filter
 fn search_kann(ctx: &Context, vec_arg: Vec<RedisString>) -> RedisResult {
@@ -599,66 +1039,217 @@ fn search_kann(ctx: &Context, vec_arg: Vec<RedisString>) -> RedisResult {
     if len <= 3 {
         return Err(RedisError::WrongArity);
     }
+    let timer = stats::Timer::new(stats::Op::Search);
 
     let mut args = vec_arg.into_iter().skip(1);
-    let name = format!("{}.{}", PREFIX, args.next_str()?);
+    let name = index_key_name(ctx, args.next_str()?, false)?;
     let k = args.next_u64()? as usize;
 
     // get redisType value
     let index_name = ctx.create_string(name.clone());
-    let key = ctx.open_key_writable(&index_name);
+    let key = ctx.open_key(&index_name);
     let index_redis = key
         .get_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE)?
-        .ok_or_else(|| RedisError::String(format!("Index: {} does not exist", name)))?;
-    let idx = index_redis.index.clone().unwrap();
-
-    let vector_str = args.next_string()?;
-    let vector_str: Vec<&str> = vector_str.split(",").collect();
-    if vector_str.len() != idx.dimensions() {
-        return Err(RedisError::WrongArity);
-    }
-    let mut data: Vec<f64> = Vec::with_capacity(idx.dimensions());
-    for item in vector_str.iter() {
-        let q = item.parse::<f64>()?;
-        data.push(q);
-    }
+        .ok_or_else(|| errors::no_index(&name))?;
+    let vector_arg = args.next_string()?;
 
     // fliter string
+    let mut ef_search = None;
     if len > 4 {
         let filter = args.next_string()?;
         ctx.log_debug(format!("set search filter: {filter:?}").as_str());
     }
 
     // search params
+    let mut model = None;
     if len > 5 {
         if (len - 5) % 2 != 0 {
-            ctx.log_debug(format!("search_params_len: {:?}%%2 != 0", len - 5).as_str());
-            return Err(RedisError::WrongArity);
+            return Err(errors::bad_param(format!(
+                "search params {} are not key value pairs",
+                len - 5
+            )));
         }
-        let ef_search_str = args.next_string()?;
-        if ef_search_str.to_lowercase() != "ef_search" {
-            ctx.log_debug(format!("need ef_search params but {ef_search_str:?}").as_str());
-            return Err(RedisError::WrongArity);
+        while let Ok(param) = args.next_string() {
+            match param.to_lowercase().as_str() {
+                "ef_search" => {
+                    ef_search = Some(args.next_u64()? as usize);
+                    ctx.log_debug(format!("set search params ef_search: {ef_search:?}").as_str());
+                }
+                "model" => model = Some(args.next_string()?),
+                _ => return Err(errors::bad_param(format!("unknown param {}", param))),
+            }
         }
+    }
+    check_model(index_redis, model.as_ref())?;
+    let data = parse_vector(index_redis, &vector_arg)?;
+
+    let search_index = SearchIndex::new(&name, index_redis);
+
+    // search on a worker, the main thread only looks up the index key and replies
+    if pool::can_dispatch(ctx) {
+        pool::dispatch(ctx, move |blocked_client| {
+            let _timer = timer;
+            let thread_ctx = ThreadSafeContext::with_blocked_client(blocked_client);
+            let res = search_with_lock(None, &search_index, ef_search, &data, k);
+            let reply = {
+                let ctx = thread_ctx.lock();
+                let index_name = ctx.create_string(name.clone());
+                let key = ctx.open_key(&index_name);
+                match key.get_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE) {
+                    Ok(Some(index_redis)) => search_reply(index_redis, res),
+                    Ok(None) => Err(errors::no_index(&name)),
+                    Err(e) => Err(e),
+                }
+            };
+            thread_ctx.reply(reply);
+        })?;
+        return Ok(RedisValue::NoReply);
+    }
+
+    let res = search_with_lock(Some(ctx), &search_index, ef_search, &data, k);
+    search_reply(index_redis, res)
+}
+
+// SearchIndex
+// the partitions and locks of the searched index, they're moved to the search worker
+struct SearchIndex {
+    name: String,
+    partitions: Vec<Arc<usearch::Index>>,
+    search_lock: Arc<RwLock<()>>,
+    expansion_lock: Arc<RwLock<()>>,
+}
 
-        let ef_search = args.next_u64()? as usize;
-        idx.change_expansion_search(ef_search)?;
-        ctx.log_debug(format!("set search params ef_search: {ef_search:?}").as_str());
+impl SearchIndex {
+    fn new(name: &str, index_redis: &IndexRedis) -> Self {
+        SearchIndex {
+            name: name.to_string(),
+            partitions: index_redis.all_partitions(),
+            search_lock: index_redis.search_lock.clone(),
+            expansion_lock: index_redis.expansion_lock.clone(),
+        }
     }
+}
 
-    // note: maybe return removed node, usearch v3 todo
-    let res = idx.search(&data, k);
-    if res.is_err() {
-        return Err(RedisError::String(format!(
-            "Index {} search ann err {}",
-            name,
-            res.err().unwrap()
-        )));
+// search_with_lock
+// partitions are searched one by one, then merged.
+// ctx is given when the search runs on the main thread, the lock wait is bounded like the index writes.
+fn search_with_lock(
+    ctx: Option<&Context>,
+    index: &SearchIndex,
+    ef_search: Option<usize>,
+    data: &[f64],
+    k: usize,
+) -> Result<usearch::ffi::Matches, RedisError> {
+    search_with_lock_profile(ctx, index, ef_search, data, k).map(|(matches, _)| matches)
+}
+
+fn search_with_lock_profile(
+    ctx: Option<&Context>,
+    index: &SearchIndex,
+    ef_search: Option<usize>,
+    data: &[f64],
+    k: usize,
+) -> Result<(usearch::ffi::Matches, SearchProfile), RedisError> {
+    let _guard = lock_search(ctx, index, ef_search)?;
+    search_partitions_profile(&index.partitions, data, k)
+        .map_err(|e| RedisError::String(format!("Index {} search ann err {}", index.name, e)))
+}
+
+// SearchGuard
+// the locks held by a search, the expansion changed by ef_search is restored before the locks are released
+struct SearchGuard<'a> {
+    _expansion: Option<ExpansionGuard<'a>>,
+    _expansion_write: Option<RwLockWriteGuard<'a, ()>>,
+    _expansion_read: Option<RwLockReadGuard<'a, ()>>,
+    _search: RwLockReadGuard<'a, ()>,
+}
+
+// lock_search
+// searches hold the search read lock, so the index writes on the main thread wait for them.
+// the usearch binding has no per query expansion, searches with ef_search hold the expansion write lock
+// to change the expansion of the partitions, the others hold the expansion read lock,
+// so ef_search queries wait for the other searches of the index, but they don't block the index writes.
+fn lock_search<'a>(
+    ctx: Option<&Context>,
+    index: &'a SearchIndex,
+    ef_search: Option<usize>,
+) -> Result<SearchGuard<'a>, RedisError> {
+    let read = |lock: &'a RwLock<()>| match ctx {
+        Some(ctx) => lock::read_lock(ctx, &index.name, lock),
+        None => Ok(lock.read().unwrap_or_else(|e| e.into_inner())),
+    };
+    let search = read(&index.search_lock)?;
+    let ef_search = match ef_search {
+        Some(ef_search) => ef_search,
+        None => {
+            return Ok(SearchGuard {
+                _expansion: None,
+                _expansion_write: None,
+                _expansion_read: Some(read(&index.expansion_lock)?),
+                _search: search,
+            })
+        }
+    };
+
+    let expansion_write = match ctx {
+        Some(ctx) => lock::write_lock(ctx, &index.name, &index.expansion_lock)?,
+        None => index
+            .expansion_lock
+            .write()
+            .unwrap_or_else(|e| e.into_inner()),
+    };
+    let expansion =
+        ExpansionGuard::new(&index.partitions, ef_search).map_err(RedisError::String)?;
+    Ok(SearchGuard {
+        _expansion: Some(expansion),
+        _expansion_write: Some(expansion_write),
+        _expansion_read: None,
+        _search: search,
+    })
+}
+
+// ExpansionGuard
+// the expansion of the partitions is changed for one query, and restored on drop
+struct ExpansionGuard<'a> {
+    partitions: &'a [Arc<usearch::Index>],
+    expansions: Vec<usize>,
+}
+
+impl<'a> ExpansionGuard<'a> {
+    fn new(partitions: &'a [Arc<usearch::Index>], ef_search: usize) -> Result<Self, String> {
+        let guard = ExpansionGuard {
+            partitions,
+            expansions: partitions
+                .iter()
+                .map(|idx| idx.expansion_search())
+                .collect(),
+        };
+        for idx in partitions.iter() {
+            idx.change_expansion_search(ef_search)
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(guard)
+    }
+}
+
+impl Drop for ExpansionGuard<'_> {
+    fn drop(&mut self) {
+        for (idx, expansion) in self.partitions.iter().zip(self.expansions.iter()) {
+            let _ = idx.change_expansion_search(*expansion);
+        }
     }
-    ctx.log_debug(format!("{res:?}").as_str());
+}
+
+// search_reply
+// map the searched vector ids to node names of the index
+fn search_reply(
+    index_redis: &IndexRedis,
+    res: Result<usearch::ffi::Matches, RedisError>,
+) -> RedisResult {
+    // note: maybe return removed node, usearch v3 todo
+    let matches = res?;
 
     let mut reply: Vec<RedisValue> = Vec::new();
-    let matches = res.unwrap();
     let l = matches.keys.len();
     for i in 0..l {
         // removed node
@@ -667,9 +1258,9 @@ fn search_kann(ctx: &Context, vec_arg: Vec<RedisString>) -> RedisResult {
         }
         let mut sr = types::SearchResultRedis::default();
         sr.id = matches.keys[i] as usize;
-        sr.name = match index_redis.node_id_name_map.entry(sr.id) {
-            Entry::Occupied(o) => o.get().to_string(),
-            Entry::Vacant(_) => "".to_string(),
+        sr.name = match index_redis.node_id_name_map.get(&sr.id) {
+            Some(node_name) => node_name.to_string(),
+            None => "".to_string(),
         };
         sr.sim = matches.distances[i] as f64;
         reply.push(sr.into());
@@ -682,6 +1273,176 @@ fn search_kann(ctx: &Context, vec_arg: Vec<RedisString>) -> RedisResult {
     Ok(ok_reply.into())
 }
 
+// search_profile
+// kANN search with the time spent of each partition, to tune connectivity and ef_search
+// cmd: usearch.search.profile indexName topK queryVector [param_key param_value]
+// cmd eg: usearch.search.profile usearch.idx0 6 0.0,0.0,0.0 ef_search 10
+// return ["results", [top K ANN node infos], "profile", [usec, ef_search, merge_usec, partitions]] or error
+// note: the usearch binding doesn't expose the visited nodes and distance computations of a search,
+// so only the time spent and the size of each partition are profiled.
+fn search_profile(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    ctx.auto_memory();
+
+    let len = args.len();
+    if len < 4 || (len - 4) % 2 != 0 {
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+    let name = index_key_name(ctx, args.next_str()?, false)?;
+    let k = args.next_u64()? as usize;
+
+    let index_name = ctx.create_string(name.clone());
+    let key = ctx.open_key(&index_name);
+    let index_redis = key
+        .get_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE)?
+        .ok_or_else(|| errors::no_index(name.as_str()))?;
+    let vector_arg = args.next_string()?;
+
+    let mut ef_search = None;
+    let mut model = None;
+    while let Ok(param) = args.next_string() {
+        match param.to_lowercase().as_str() {
+            "ef_search" => ef_search = Some(args.next_u64()? as usize),
+            "model" => model = Some(args.next_string()?),
+            _ => return Err(errors::bad_param(format!("unknown param {}", param))),
+        }
+    }
+    check_model(index_redis, model.as_ref())?;
+    let data = parse_vector(index_redis, &vector_arg)?;
+
+    let search_index = SearchIndex::new(&name, index_redis);
+
+    // profile on a worker like kann search, the main thread only looks up the index key and replies
+    if pool::can_dispatch(ctx) {
+        pool::dispatch(ctx, move |blocked_client| {
+            let thread_ctx = ThreadSafeContext::with_blocked_client(blocked_client);
+            let (res, usec) = search_profiled(None, &search_index, ef_search, &data, k);
+            let reply = {
+                let ctx = thread_ctx.lock();
+                let index_name = ctx.create_string(name.clone());
+                let key = ctx.open_key(&index_name);
+                match key.get_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE) {
+                    Ok(Some(index_redis)) => {
+                        profile_reply(index_redis, &search_index.partitions, ef_search, res, usec)
+                    }
+                    Ok(None) => Err(errors::no_index(&name)),
+                    Err(e) => Err(e),
+                }
+            };
+            thread_ctx.reply(reply);
+        })?;
+        return Ok(RedisValue::NoReply);
+    }
+
+    let (res, usec) = search_profiled(Some(ctx), &search_index, ef_search, &data, k);
+    profile_reply(index_redis, &search_index.partitions, ef_search, res, usec)
+}
+
+// search with the profile of each partition, and the whole time spent in usec
+fn search_profiled(
+    ctx: Option<&Context>,
+    index: &SearchIndex,
+    ef_search: Option<usize>,
+    data: &[f64],
+    k: usize,
+) -> (
+    Result<(usearch::ffi::Matches, SearchProfile), RedisError>,
+    i64,
+) {
+    let start = Instant::now();
+    let res = search_with_lock_profile(ctx, index, ef_search, data, k);
+    (res, start.elapsed().as_micros() as i64)
+}
+
+fn profile_reply(
+    index_redis: &IndexRedis,
+    partitions: &[Arc<usearch::Index>],
+    ef_search: Option<usize>,
+    res: Result<(usearch::ffi::Matches, SearchProfile), RedisError>,
+    usec: i64,
+) -> RedisResult {
+    let (matches, profile) = match res {
+        Ok((matches, profile)) => (Ok(matches), profile),
+        Err(e) => (Err(e), SearchProfile::default()),
+    };
+    let results = search_reply(index_redis, matches)?;
+
+    let partitions_profile = partitions
+        .iter()
+        .zip(profile.partitions_usec)
+        .enumerate()
+        .map(|(i, (idx, partition_usec))| {
+            let partition: Vec<RedisValue> = vec![
+                "partition".into(),
+                i.into(),
+                "size".into(),
+                idx.size().into(),
+                "usec".into(),
+                (partition_usec as i64).into(),
+            ];
+            partition.into()
+        })
+        .collect::<Vec<RedisValue>>();
+
+    let profile: Vec<RedisValue> = vec![
+        "usec".into(),
+        usec.into(),
+        "ef_search".into(),
+        ef_search
+            .unwrap_or_else(|| partitions.first().map_or(0, |idx| idx.expansion_search()))
+            .into(),
+        "merge_usec".into(),
+        (profile.merge_usec as i64).into(),
+        "partitions".into(),
+        partitions_profile.into(),
+    ];
+
+    Ok(vec!["results".into(), results, "profile".into(), profile.into()].into())
+}
+
+// dkann
+// cmd: usearch.search.dkann indexPattern topK queryVector [timeout ms]
+// cmd eg: usearch.search.dkann usearch.emb_* 6 0.0,0.0,0.0 timeout 500
+// return merged top K ANN node infos of the indexes matched the pattern on all shards,
+// [count, partial, node...], partial is 1 if some shards fail or timeout
+fn dkann(ctx: &Context, args: Vec<RedisString>) -> RedisResult {
+    ctx.auto_memory();
+    let len = args.len();
+    if len != 4 && len != 6 {
+        return Err(RedisError::WrongArity);
+    }
+
+    let mut args = args.into_iter().skip(1);
+    let pattern = args.next_string()?;
+    let k = args.next_u64()? as usize;
+
+    let vector_str = args.next_string()?;
+    let mut data: Vec<f64> = Vec::new();
+    for item in vector_str.split(",") {
+        let q = item
+            .parse::<f64>()
+            .map_err(|e| errors::bad_param(format!("vector item {} err {}", item, e)))?;
+        data.push(q);
+    }
+
+    let mut timeout_ms = cluster::DKANN_DEFAULT_TIMEOUT_MS;
+    if len == 6 {
+        let param = args.next_string()?;
+        if param.to_lowercase() != "timeout" {
+            return Err(errors::bad_param(format!(
+                "need timeout params but {param:?}"
+            )));
+        }
+        timeout_ms = args.next_i64()?;
+        if timeout_ms <= 0 {
+            return Err(errors::bad_param("timeout must be > 0"));
+        }
+    }
+
+    cluster::dkann(ctx, pattern, k, data, timeout_ms)
+}
+
 #[cfg(not(test))]
 macro_rules! get_allocator {
     () => {
@@ -702,39 +1463,85 @@ redis_module! {
     allocator: (get_allocator!(), get_allocator!()),
     data_types: [USEARCH_INDEX_REDIS_TYPE],
     init: init,
+    info: add_info,
+    // key specs: the index key name arg is the 1st key, e.g. usearch.idx0,
+    // so redis checks the acl key patterns and cluster clients route the cmds of one index to its node.
     commands: [
-        [format!("{}.index.create", PREFIX), create_index, "write", 0, 0, 0],
-        [format!("{}.index.get", PREFIX), get_index, "readonly", 0, 0, 0],
-        [format!("{}.index.del", PREFIX), del_index, "write", 0, 0, 0],
-        [format!("{}.node.add", PREFIX), add_node, "write", 0, 0, 0],
-        [format!("{}.node.add_id", PREFIX), add_vector, "write", 0, 0, 0],
-        [format!("{}.node.get", PREFIX), get_node, "readonly", 0, 0, 0],
-        [format!("{}.node.get_id", PREFIX), get_vector, "readonly", 0, 0, 0],
-        [format!("{}.node.del", PREFIX), delete_node, "write", 0, 0, 0],
-        [format!("{}.node.del_id", PREFIX), delete_vector, "write", 0, 0, 0],
-        [format!("{}.search.kann", PREFIX), search_kann, "readonly", 0, 0, 0],
+        [format!("{}.index.create", PREFIX), create_index, "write deny-oom", 1, 1, 1],
+        [format!("{}.index.get", PREFIX), get_index, "readonly fast", 1, 1, 1],
+        [format!("{}.index.del", PREFIX), del_index, "write", 1, 1, 1],
+        [format!("{}.index.list", PREFIX), list_index, "readonly", 0, 0, 0],
+        [format!("{}.index.rebuild", PREFIX), rebuild_index, "write deny-oom", 1, 2, 1],
+        [format!("{}.index.swap", PREFIX), swap_index, "write deny-oom", 1, 1, 1],
+        [format!("{}.alias.set", PREFIX), set_alias, "write", 1, 2, 1],
+        [format!("{}.alias.del", PREFIX), del_alias, "write fast", 1, 1, 1],
+        [format!("{}.alias.list", PREFIX), list_alias, "readonly", 0, 0, 0],
+        [format!("{}.node.add", PREFIX), add_node, "write deny-oom", 1, 1, 1],
+        [format!("{}.node.add_id", PREFIX), add_vector, "write deny-oom", 1, 1, 1],
+        [format!("{}.node.get", PREFIX), get_node, "readonly fast", 1, 1, 1],
+        [format!("{}.node.get_id", PREFIX), get_vector, "readonly fast", 1, 1, 1],
+        [format!("{}.node.del", PREFIX), delete_node, "write fast", 1, 1, 1],
+        [format!("{}.node.del_id", PREFIX), delete_vector, "write fast", 1, 1, 1],
+        [format!("{}.node.scan", PREFIX), scan_node, "readonly", 1, 1, 1],
+        [format!("{}.search.kann", PREFIX), search_kann, "readonly", 1, 1, 1],
+        [format!("{}.search.profile", PREFIX), search_profile, "readonly", 1, 1, 1],
+        // index pattern isn't a key, results are gathered from all shards
+        [format!("{}.search.dkann", PREFIX), dkann, "readonly", 0, 0, 0],
+    ],
+    event_handlers: [
+        [@GENERIC @EXPIRED @EVICTED @LOADED: events::on_keyspace_event],
+    ],
+    // CONFIG SET/GET usearch.<name>, numeric configs are validated in [min, max] by redis
+    configurations: [
+        i64: [
+            ["reserve_cap", &*config::RESERVE_CAP, config::DEFAULT_RESERVE_CAP, 1, config::MAX_RESERVE_CAP, ConfigurationFlags::DEFAULT, None],
+            ["ef_search", &*config::EF_SEARCH, config::DEFAULT_EF_SEARCH, 1, config::MAX_EF_SEARCH, ConfigurationFlags::DEFAULT, None],
+            ["search_threads", &*config::SEARCH_THREADS, config::DEFAULT_SEARCH_THREADS, 0, config::MAX_SEARCH_THREADS, ConfigurationFlags::DEFAULT, Some(Box::new(config::on_search_threads_changed))],
+        ],
+        string: [
+            ["serialization_file_path_dir", &*config::SERIALIZATION_FILE_PATH_DIR, "", ConfigurationFlags::DEFAULT, None],
+        ],
+        bool: [
+            ["remove_serialized_file", &*config::REMOVE_SERIALIZED_FILE, false, ConfigurationFlags::DEFAULT, None],
+            ["notify_events", &*config::NOTIFY_EVENTS, true, ConfigurationFlags::DEFAULT, None],
+        ],
     ],
 }
 
 fn init(ctx: &Context, args: &[RedisString]) -> Status {
-    if args.len() % 2 != 0 {
-        ctx.log_warning(
-            format!(
-                "module arguments len {}, must be key:value pairs",
-                args.len()
-            )
-            .as_str(),
-        );
+    if let Err(e) = config::apply_module_args(ctx, args) {
+        ctx.log_warning(e.as_str());
+        return Status::Err;
+    }
+
+    if let Err(e) = events::subscribe_server_events(ctx) {
+        ctx.log_warning(format!("{}", e).as_str());
         return Status::Err;
     }
+    cluster::register_cluster_receivers(ctx);
 
-    for i in (0..args.len()).step_by(2) {
-        MODULE_ARGS_MAP.write().unwrap().insert(
-            args[i].to_string_lossy().to_string(),
-            args[i + 1].to_string_lossy().to_string(),
+    // searches run on the worker pool, search_threads 0 runs them on the main thread
+    pool::init(PREFIX, config::search_threads());
+
+    // rdb load callbacks check the IO errors, redis doesn't abort on them
+    unsafe {
+        raw::RedisModule_SetModuleOptions.unwrap()(
+            ctx.ctx,
+            raw::REDISMODULE_OPTIONS_HANDLE_IO_ERRORS as c_int,
         );
     }
-    ctx.log_debug(format!("{:?}", MODULE_ARGS_MAP.read().unwrap()).as_str());
 
     Status::Ok
 }
+
+// add_info
+// INFO usearch / INFO everything: the usearch_redisxann section, see info::add_info
+fn add_info(ctx: &InfoContext, for_crash_report: bool) {
+    info::add_info(
+        ctx,
+        for_crash_report,
+        &USEARCH_INDEX_REDIS_TYPE,
+        |index: &IndexRedis| index.size(),
+        |_, index: &IndexRedis| index.mem_usage(info::INFO_MEM_SAMPLES).total(),
+    );
+}
@@ -0,0 +1,329 @@
+use std::collections::{HashMap, HashSet};
+use std::os::raw::c_int;
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+
+use redis_module::{raw, Context, RedisError, RedisString, ThreadSafeContext};
+use serde::de::DeserializeOwned;
+use usearch::Index;
+
+use crate::types::*;
+use crate::{events, get_selected_db, index_file_path, registry};
+
+// vectors are read from the source index in batches with the search read lock,
+// so writes to the source index on the main thread aren't blocked for long.
+static REBUILD_BATCH_SIZE: usize = 1024;
+
+lazy_static! {
+    // dst index keys which are being rebuilt, (db, dst index key name) -> source changes
+    static ref REBUILDING: Mutex<HashMap<(i32, String), Changes>> = Mutex::new(HashMap::new());
+}
+
+// Changes
+// change log of the source index during a rebuild, ids added, overwritten or deleted after the snapshot,
+// the built vectors of the ids maybe stale, they are read from the source index again on swap.
+struct Changes {
+    src: String,
+    ids: HashSet<u64>,
+}
+
+// changed
+// log the id written to the index key in db, if the index is the source of rebuilds
+pub fn changed(db: i32, name: &str, id: u64) {
+    let mut rebuilding = REBUILDING.lock().unwrap();
+    for ((d, _), changes) in rebuilding.iter_mut() {
+        if *d == db && changes.src == name {
+            changes.ids.insert(id);
+        }
+    }
+}
+
+// RebuildJob
+// snapshot of the source index to build the new index in background
+struct RebuildJob {
+    db: i32,
+    src: String,
+    dst: String,
+    opts: IndexOpts,
+    partitions: usize,
+    src_partitions: Vec<Arc<Index>>,
+    src_search_lock: Arc<RwLock<()>>,
+    ids: Vec<u64>,
+}
+
+// the new index built from the source vectors, not visible until swapped
+struct BuiltIndex {
+    index: Arc<Index>,
+    partitions: Vec<Arc<Index>>,
+    ids: HashSet<u64>,
+}
+
+impl BuiltIndex {
+    fn partition(&self, id: u64) -> Arc<Index> {
+        match partition_of(id, self.partitions.len() + 1) {
+            0 => self.index.clone(),
+            i => self.partitions[i - 1].clone(),
+        }
+    }
+}
+
+// rebuild
+// snapshot the source index ids on the main thread, then build and swap in a background thread.
+pub fn rebuild(
+    ctx: &Context,
+    src_index: &IndexRedis,
+    dst: String,
+    opts: IndexOpts,
+    partitions: usize,
+) -> Result<(), RedisError> {
+    let db = get_selected_db(ctx);
+    {
+        let mut rebuilding = REBUILDING.lock().unwrap();
+        if rebuilding.contains_key(&(db, dst.clone())) {
+            return Err(RedisError::String(format!(
+                "Index: {} is being rebuilt",
+                dst
+            )));
+        }
+        let changes = Changes {
+            src: src_index.name.clone(),
+            ids: HashSet::new(),
+        };
+        rebuilding.insert((db, dst.clone()), changes);
+    }
+
+    let job = RebuildJob {
+        db,
+        src: src_index.name.clone(),
+        dst,
+        opts,
+        partitions,
+        src_partitions: src_index.all_partitions(),
+        src_search_lock: src_index.search_lock.clone(),
+        ids: src_index.ids().map(|id| id as u64).collect(),
+    };
+    thread::spawn(move || {
+        let res = build(&job).and_then(|built| swap(&job, built));
+        REBUILDING
+            .lock()
+            .unwrap()
+            .remove(&(job.db, job.dst.clone()));
+
+        let thread_ctx = ThreadSafeContext::new();
+        let ctx = thread_ctx.lock();
+        match res {
+            Ok(n) => ctx.log_notice(
+                format!(
+                    "rebuild Index: {} to {} with {} nodes ok",
+                    job.src, job.dst, n
+                )
+                .as_str(),
+            ),
+            Err(e) => ctx.log_warning(
+                format!("rebuild Index: {} to {} err {}", job.src, job.dst, e).as_str(),
+            ),
+        }
+    });
+
+    Ok(())
+}
+
+fn reserve_cap(n: usize) -> usize {
+    n + n / 2 + 1
+}
+
+// build the new index from the source vectors without the redis lock
+fn build(job: &RebuildJob) -> Result<BuiltIndex, String> {
+    let cap = reserve_cap(job.ids.len() / job.partitions);
+    let mut indexes = Vec::with_capacity(job.partitions);
+    for _ in 0..job.partitions {
+        indexes.push(Arc::new(new_usearch_index(&job.opts, cap)?));
+    }
+    let index = indexes.remove(0);
+    let mut built = BuiltIndex {
+        index,
+        partitions: indexes,
+        ids: HashSet::with_capacity(job.ids.len()),
+    };
+
+    let n = job.src_partitions.len();
+    let mut vector = vec![0.0 as f64; job.opts.dimensions];
+    for batch in job.ids.chunks(REBUILD_BATCH_SIZE) {
+        let mut vectors: Vec<(u64, Vec<f64>)> = Vec::with_capacity(batch.len());
+        {
+            let _guard = job.src_search_lock.read().unwrap();
+            for id in batch {
+                let src = &job.src_partitions[partition_of(*id, n)];
+                // the node maybe deleted after the snapshot
+                if src.get(*id, &mut vector).map_err(|e| e.to_string())? == 0 {
+                    continue;
+                }
+                vectors.push((*id, vector.clone()));
+            }
+        }
+        for (id, vector) in vectors {
+            add(&built.partition(id), id, &vector)?;
+            built.ids.insert(id);
+        }
+    }
+    Ok(built)
+}
+
+fn add(idx: &Index, id: u64, vector: &[f64]) -> Result<(), String> {
+    if idx.size() >= idx.capacity() {
+        idx.reserve(reserve_cap(idx.capacity()))
+            .map_err(|e| e.to_string())?;
+    }
+    idx.add(id, vector).map_err(|e| e.to_string())
+}
+
+// swap
+// apply the source index changes during the build, then set the new index to the dst key
+// with the redis lock, so clients see the old or the new index.
+fn swap(job: &RebuildJob, built: BuiltIndex) -> Result<usize, String> {
+    let thread_ctx = ThreadSafeContext::new();
+    let ctx = thread_ctx.lock();
+    let res = unsafe { raw::RedisModule_SelectDb.unwrap()(ctx.ctx, job.db) };
+    if res != raw::REDISMODULE_OK as c_int {
+        return Err(format!("select db {} err", job.db));
+    }
+
+    let src_name = ctx.create_string(job.src.clone());
+    let src_key = ctx.open_key(&src_name);
+    let src_index = src_key
+        .get_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Index: {} does not exist", job.src))?;
+    if src_index.index_opts.dimensions != job.opts.dimensions {
+        return Err(format!("Index: {} dimensions are changed", job.src));
+    }
+
+    let dst_name = ctx.create_string(job.dst.clone());
+    let dst_key = ctx.open_key_writable(&dst_name);
+    // dst maybe created during the build
+    if job.dst != job.src && !dst_key.is_empty() {
+        return Err(format!("Index: {} already exists", job.dst));
+    }
+
+    // nodes added, overwritten or deleted during the build
+    let changed = REBUILDING
+        .lock()
+        .unwrap()
+        .get_mut(&(job.db, job.dst.clone()))
+        .map(|changes| std::mem::take(&mut changes.ids))
+        .unwrap_or_default();
+    let mut vector = vec![0.0 as f64; job.opts.dimensions];
+    for id in src_index.ids() {
+        let id = id as u64;
+        if built.ids.contains(&id) {
+            if !changed.contains(&id) {
+                continue;
+            }
+            // the built vector maybe stale
+            built.partition(id).remove(id).map_err(|e| e.to_string())?;
+        }
+        if src_index
+            .partition(id)
+            .get(id, &mut vector)
+            .map_err(|e| e.to_string())?
+            > 0
+        {
+            add(&built.partition(id), id, &vector)?;
+        }
+    }
+    for id in built.ids.iter() {
+        if !src_index.contains_id(*id as usize) {
+            built
+                .partition(*id)
+                .remove(*id)
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    let mut index = IndexRedis::default();
+    index.name = job.dst.clone();
+    index.index_opts = job.opts.clone();
+    index.node_id_name_map = src_index
+        .node_id_name_map
+        .iter()
+        .map(|(id, node_name)| (*id, rename_node(node_name, &job.src, &job.dst)))
+        .collect();
+    index.node_name_id_map = src_index
+        .node_name_id_map
+        .iter()
+        .map(|(node_name, id)| (rename_node(node_name, &job.src, &job.dst), *id))
+        .collect::<HashMap<String, usize>>();
+    index.vector_ids = src_index.vector_ids.clone();
+    index.index = Some(built.index);
+    index.partitions = built.partitions;
+    index.serialization_file_path = index_file_path(job.db, &job.dst);
+    let n = index.node_count();
+    let args = swap_args(&index)?;
+
+    dst_key
+        .set_value::<IndexRedis>(&USEARCH_INDEX_REDIS_TYPE, index)
+        .map_err(|e| e.to_string())?;
+    registry::add(job.db, &job.dst);
+    events::notify_keyspace_event(&ctx, &format!("{}.index.rebuild", crate::PREFIX), &job.dst);
+    // replicas and the aof set the swapped index instead of rebuilding it,
+    // so they swap at the same point of the write stream as the master.
+    let args = args.iter().collect::<Vec<&Vec<u8>>>();
+    ctx.replicate(&format!("{}.index.swap", crate::PREFIX), args.as_slice());
+    Ok(n)
+}
+
+// swap_args
+// the args of usearch.index.swap: the index key name, opts, node maps and the serialized partitions
+fn swap_args(index: &IndexRedis) -> Result<Vec<Vec<u8>>, String> {
+    let json_err = |e: serde_json::Error| e.to_string();
+    let mut args = vec![
+        index.name.clone().into_bytes(),
+        serde_json::to_vec(&index.index_opts).map_err(json_err)?,
+        serde_json::to_vec(&index.node_id_name_map).map_err(json_err)?,
+        serde_json::to_vec(&index.node_name_id_map).map_err(json_err)?,
+        serde_json::to_vec(&index.vector_ids).map_err(json_err)?,
+    ];
+    for (i, partition) in index.all_partitions().iter().enumerate() {
+        let mut buffer = vec![0_u8; partition.serialized_length()];
+        partition
+            .save_to_buffer(&mut buffer)
+            .map_err(|e| format!("save partition {} to buffer err {}", i, e))?;
+        args.push(buffer);
+    }
+    Ok(args)
+}
+
+// swapped_index
+// load the index of the usearch.index.swap args which are replicated by swap
+pub fn swapped_index(db: i32, args: &[RedisString]) -> Result<IndexRedis, String> {
+    if args.len() < 6 || args.len() - 5 > crate::MAX_INDEX_PARTITIONS {
+        return Err(format!(
+            "swap args {} not in [6, {}]",
+            args.len(),
+            crate::MAX_INDEX_PARTITIONS + 5
+        ));
+    }
+    let mut index = IndexRedis::default();
+    index.name = args[0].try_as_str().map_err(|e| e.to_string())?.to_string();
+    index.index_opts = from_json(&args[1], "index opts")?;
+    index.node_id_name_map = from_json(&args[2], "node id name map")?;
+    index.node_name_id_map = from_json(&args[3], "node name id map")?;
+    index.vector_ids = from_json(&args[4], "vector ids")?;
+    for (i, buffer) in args[5..].iter().enumerate() {
+        let idx = new_usearch_index(&index.index_opts, 0)?;
+        idx.load_from_buffer(buffer.as_slice())
+            .map_err(|e| format!("load partition {} err {}", i, e))?;
+        idx.reserve(reserve_cap(idx.size()))
+            .map_err(|e| e.to_string())?;
+        match i {
+            0 => index.index = Some(Arc::new(idx)),
+            _ => index.partitions.push(Arc::new(idx)),
+        }
+    }
+    index.serialization_file_path = index_file_path(db, &index.name);
+    Ok(index)
+}
+
+fn from_json<T: DeserializeOwned>(arg: &RedisString, what: &str) -> Result<T, String> {
+    serde_json::from_slice(arg.as_slice()).map_err(|e| format!("{} json err {}", what, e))
+}
@@ -0,0 +1,163 @@
+use std::collections::{BTreeSet, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use redis_module::logging;
+use usearch::Index;
+
+use crate::config;
+
+// serialized usearch index files are kept in the managed dir <serialization_file_path_dir>/usearch,
+// file names are encoded from the db and index key name, so key names can't escape the dir,
+// the files assigned to indexes are listed in the manifest, orphaned files are removed by gc.
+static STORAGE_DIR: &str = "usearch";
+static MANIFEST_FILE: &str = "MANIFEST.json";
+static SUFFIX: &str = "idx";
+static TMP_SUFFIX: &str = "tmp";
+
+lazy_static! {
+    // manifest read-modify-write lock
+    static ref MANIFEST_LOCK: Mutex<()> = Mutex::new(());
+}
+
+pub fn storage_dir() -> PathBuf {
+    Path::new(&config::serialization_file_path_dir()).join(STORAGE_DIR)
+}
+
+// file_name
+// <db>.<encoded index key name>.idx, the bytes of the key name except [A-Za-z0-9._-] are %XX encoded,
+// e.g. usearch.idx0 in db 0 is 0.usearch.idx0.idx, usearch.a/../b is 0.usearch.a%2F..%2Fb.idx
+pub fn file_name(db: i32, name: &str) -> String {
+    let mut encoded = String::with_capacity(name.len());
+    for b in name.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'.' | b'_' | b'-' => encoded.push(b as char),
+            _ => encoded.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    format!("{}.{}.{}", db, encoded, SUFFIX)
+}
+
+// serialization file path of the index key name in db, it isn't assigned
+pub fn file_path(db: i32, name: &str) -> String {
+    storage_dir()
+        .join(file_name(db, name))
+        .to_string_lossy()
+        .to_string()
+}
+
+// assign
+// assign the serialization file of the index key name in db,
+// the managed dir is created and the file is listed in the manifest.
+// note: errors are logged, the index is saved in the file on the next rdb save.
+pub fn assign(db: i32, name: &str) -> String {
+    let dir = storage_dir();
+    let file = file_name(db, name);
+    if let Err(e) = fs::create_dir_all(&dir).and_then(|_| {
+        update_manifest(&dir, |files| {
+            files.insert(file.clone());
+        })
+    }) {
+        logging::log_warning(format!(
+            "assign serialization file {} in {} err {}",
+            file,
+            dir.display(),
+            e
+        ));
+    }
+    dir.join(file).to_string_lossy().to_string()
+}
+
+// save
+// write the index to a tmp file then rename it to the path,
+// so a crashed save never leaves a partial serialization file.
+pub fn save(idx: &Index, path: &str) -> Result<(), String> {
+    let tmp_path = format!("{}.{}", path, TMP_SUFFIX);
+    idx.save(tmp_path.as_str()).map_err(|e| e.to_string())?;
+    fs::rename(&tmp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp_path);
+        format!("rename {} to {} err {}", tmp_path, path, e)
+    })
+}
+
+// remove the serialization file and unlist it from the manifest if it's in the managed dir
+pub fn remove(path: &str) -> io::Result<()> {
+    let path = Path::new(path);
+    let dir = storage_dir();
+    if path.parent() == Some(dir.as_path()) {
+        if let Some(file) = path.file_name() {
+            let file = file.to_string_lossy().to_string();
+            update_manifest(&dir, |files| {
+                files.remove(&file);
+            })?;
+        }
+    }
+    fs::remove_file(path)
+}
+
+// gc
+// remove the manifest files which aren't the serialization files of the live indexes,
+// e.g. left by DEL of the index key, RENAME, or a rebuild, and tmp files left by crashed saves.
+// it runs after loading, the live indexes are the loaded dataset which the persisted files belong to.
+// return the removed files count
+pub fn gc(live_paths: &HashSet<String>) -> usize {
+    let dir = storage_dir();
+    let live_files: HashSet<String> = live_paths
+        .iter()
+        .map(Path::new)
+        .filter(|path| path.parent() == Some(dir.as_path()))
+        .filter_map(|path| path.file_name())
+        .map(|file| file.to_string_lossy().to_string())
+        .collect();
+
+    let mut orphans = Vec::new();
+    if let Err(e) = update_manifest(&dir, |files| {
+        orphans = files.difference(&live_files).cloned().collect();
+        files.retain(|file| live_files.contains(file));
+    }) {
+        logging::log_warning(format!("gc {} manifest err {}", dir.display(), e));
+        return 0;
+    }
+    if let Ok(entries) = fs::read_dir(&dir) {
+        let tmp_ext = format!(".{}", TMP_SUFFIX);
+        orphans.extend(
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.file_name().to_string_lossy().to_string())
+                .filter(|file| file.ends_with(&tmp_ext)),
+        );
+    }
+
+    let mut removed = 0;
+    for file in orphans {
+        match fs::remove_file(dir.join(&file)) {
+            Ok(_) => removed += 1,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => logging::log_warning(format!("gc remove {} err {}", file, e)),
+        }
+    }
+    removed
+}
+
+// update the manifest file, it's written to a tmp file then renamed
+fn update_manifest(dir: &Path, update: impl FnOnce(&mut BTreeSet<String>)) -> io::Result<()> {
+    let _guard = MANIFEST_LOCK.lock().unwrap();
+    let path = dir.join(MANIFEST_FILE);
+    let mut files: BTreeSet<String> = match fs::read(&path) {
+        Ok(data) => serde_json::from_slice(&data)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => BTreeSet::new(),
+        Err(e) => return Err(e),
+    };
+    let before = files.clone();
+    update(&mut files);
+    if files == before {
+        return Ok(());
+    }
+
+    let tmp_path = dir.join(format!("{}.{}", MANIFEST_FILE, TMP_SUFFIX));
+    fs::write(&tmp_path, serde_json::to_vec(&files)?)?;
+    fs::rename(&tmp_path, &path)
+}
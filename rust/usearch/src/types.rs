@@ -1,17 +1,28 @@
-use std::collections::HashMap;
-use std::ffi::CString;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::os::raw::{c_int, c_void};
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
 use std::{fmt, ptr};
 
 use redis_module::native_types::RedisType;
-use redis_module::{raw, RedisString, RedisValue};
+use redis_module::{logging, raw, RedisString, RedisValue};
 use serde::{Deserialize, Serialize};
 
-use usearch::ffi::{IndexOptions, MetricKind, ScalarKind};
+use usearch::ffi::{IndexOptions, Matches, MetricKind, ScalarKind};
 use usearch::Index;
+use xxhash_rust::xxh3::xxh3_64;
 
-static INDEX_VERSION: i32 = 0;
+use crate::storage;
+use modcore::defrag::Defrag;
+use modcore::digest::Digest;
+use modcore::rdb::{self, RdbResult};
+
+// rdb encoding versions, the index saved by an older encver is upgraded when it's loaded,
+// a newer encver fails the load instead of misreading the rdb.
+// encver 0: name, opts, node maps, serialization file path, capacity; the index is saved in the file
+// encver 1: PARTITIONS n, the sub indexes of a partitioned index are saved in the rdb
+// encver 2: the vector ids of usearch.node.add_id are saved apart from the node maps
+static INDEX_VERSION: i32 = 2;
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
 pub enum MKind {
@@ -99,6 +110,10 @@ pub struct IndexOpts {
     pub expansion_add: usize,
     pub expansion_search: usize,
     pub multi: bool,
+    #[serde(default)]
+    pub model: Option<String>, // embedding model which produces the vectors
+    #[serde(default)]
+    pub version: Option<String>, // embedding model version
 }
 impl Default for IndexOpts {
     fn default() -> Self {
@@ -110,6 +125,8 @@ impl Default for IndexOpts {
             expansion_add: 2,
             expansion_search: 3,
             multi: false,
+            model: None,
+            version: None,
         }
     }
 }
@@ -131,9 +148,9 @@ impl From<IndexOpts> for IndexOptions {
 
 #[derive(Default, Clone)]
 pub struct IndexRedis {
-    pub name: String,                             // index name
-    pub index_opts: IndexOpts,                    // usearch index options
-    pub node_id_name_map: HashMap<usize, String>, // usearch index node id/name map when usearch.node.add indexName nodeName
+    pub name: String,                              // index name
+    pub index_opts: IndexOpts,                     // usearch index options
+    pub node_id_name_map: BTreeMap<usize, String>, // usearch index node id/name map (ordered by id for scan)
     pub node_name_id_map: HashMap<String, usize>, // usearch index node name/id map when usearch.node.add indexName nodeName
     pub index: Option<Arc<Index>>,                // usearch index
     // pub serialization_buffer: Vec<u8>, // usearch index serialization buffer for save/load
@@ -141,11 +158,293 @@ pub struct IndexRedis {
     //pub index_size: usize,               // usearch index size
     //pub index_capacity: usize,           // usearch index capacity
     pub serialization_file_path: String, // usearch index serialization file path for save/load
+    // usearch index vector ids without node name when usearch.node.add_id (ordered by id for scan)
+    pub vector_ids: BTreeSet<usize>,
+    pub partitions: Vec<Arc<Index>>, // usearch sub indexes 1..n of PARTITIONS n, index is the partition 0
+    pub search_lock: Arc<RwLock<()>>, // searches on workers hold the read lock, index changes on the main thread hold the write lock
+    pub expansion_lock: Arc<RwLock<()>>, // searches with ef_search hold the write lock to change the expansion, the others hold the read lock
+}
+
+impl IndexRedis {
+    // deep copy the index as index key name, the usearch index graph and node maps
+    // are copied by serialization, so the copy is independent of the source index.
+    pub fn deep_copy(&self, name: &str, serialization_file_path: String) -> Result<Self, String> {
+        let src = self.index.as_ref().ok_or("usearch index un init")?;
+        let mut index = IndexRedis::default();
+        index.name = name.to_string();
+        index.index_opts = self.index_opts.clone();
+        index.node_id_name_map = self
+            .node_id_name_map
+            .iter()
+            .map(|(id, node_name)| (*id, rename_node(node_name, &self.name, name)))
+            .collect();
+        index.node_name_id_map = self
+            .node_name_id_map
+            .iter()
+            .map(|(node_name, id)| (rename_node(node_name, &self.name, name), *id))
+            .collect();
+        index.vector_ids = self.vector_ids.clone();
+        index.index = Some(Arc::new(copy_usearch_index(src, &self.index_opts, name)?));
+        for partition in self.partitions.iter() {
+            index.partitions.push(Arc::new(copy_usearch_index(
+                partition,
+                &self.index_opts,
+                name,
+            )?));
+        }
+        index.serialization_file_path = serialization_file_path;
+        Ok(index)
+    }
+
+    // rebind the index to the index key name after RENAME/MOVE/SWAPDB,
+    // node names follow the index key name, the serialization file is written to the new path on next save.
+    pub fn rebind(&mut self, name: &str, serialization_file_path: String) {
+        if self.name != name {
+            let old_name = std::mem::replace(&mut self.name, name.to_string());
+            for node_name in self.node_id_name_map.values_mut() {
+                *node_name = rename_node(node_name, &old_name, name);
+            }
+            self.node_name_id_map = self
+                .node_name_id_map
+                .drain()
+                .map(|(node_name, id)| (rename_node(&node_name, &old_name, name), id))
+                .collect();
+        }
+        self.serialization_file_path = serialization_file_path;
+    }
+
+    pub fn contains_id(&self, id: usize) -> bool {
+        self.node_id_name_map.contains_key(&id) || self.vector_ids.contains(&id)
+    }
+
+    // count of the nodes with name and the vectors without name
+    pub fn node_count(&self) -> usize {
+        self.node_id_name_map.len() + self.vector_ids.len()
+    }
+
+    // all node ids in id order
+    pub fn ids(&self) -> impl Iterator<Item = usize> + '_ {
+        self.nodes(0).map(|(id, _)| id)
+    }
+
+    // (id, node name) of the nodes from the cursor id in id order,
+    // the vectors added by usearch.node.add_id have empty name.
+    pub fn nodes(&self, cursor: usize) -> impl Iterator<Item = (usize, &str)> + '_ {
+        let mut named = self.node_id_name_map.range(cursor..).peekable();
+        let mut unnamed = self.vector_ids.range(cursor..).peekable();
+        std::iter::from_fn(move || {
+            let named_first = match (named.peek(), unnamed.peek()) {
+                (Some((id, _)), Some(vector_id)) => id < vector_id,
+                (named_next, _) => named_next.is_some(),
+            };
+            match named_first {
+                true => named.next().map(|(id, name)| (*id, name.as_str())),
+                false => unnamed.next().map(|id| (*id, "")),
+            }
+        })
+    }
+
+    // all partitions, the index is the only partition if it isn't partitioned
+    pub fn all_partitions(&self) -> Vec<Arc<Index>> {
+        let mut partitions = Vec::with_capacity(self.partitions.len() + 1);
+        partitions.extend(self.index.clone());
+        partitions.extend(self.partitions.iter().cloned());
+        partitions
+    }
+
+    // partition of the vector id, ids are split across partitions by hash
+    pub fn partition(&self, id: u64) -> Arc<Index> {
+        match partition_of(id, self.partitions.len() + 1) {
+            0 => self.index.clone().unwrap(),
+            i => self.partitions[i - 1].clone(),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.all_partitions().iter().map(|idx| idx.size()).sum()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.all_partitions().iter().map(|idx| idx.capacity()).sum()
+    }
+
+    pub fn memory_usage(&self) -> usize {
+        self.all_partitions()
+            .iter()
+            .map(|idx| idx.memory_usage())
+            .sum()
+    }
+
+    // approximate memory usage of the index value, sample_size > 0 estimates the node names
+    // by the first sample_size names of each map.
+    pub fn mem_usage(&self, sample_size: usize) -> MemUsage {
+        let names = std::mem::size_of::<Self>()
+            + self.name.capacity()
+            + self.serialization_file_path.capacity()
+            + self.index_opts.model.as_ref().map_or(0, |m| m.capacity())
+            + self.index_opts.version.as_ref().map_or(0, |v| v.capacity())
+            + strings_mem_usage(self.node_id_name_map.values(), sample_size)
+            + strings_mem_usage(self.node_name_id_map.keys(), sample_size);
+        // btree nodes hold up to 11 entries, hash tables have a control byte per bucket
+        let maps = self.node_id_name_map.len() * std::mem::size_of::<(usize, String)>() * 3 / 2
+            + self.vector_ids.len() * std::mem::size_of::<usize>() * 3 / 2
+            + self.node_name_id_map.capacity() * (std::mem::size_of::<(String, usize)>() + 1)
+            + self.partitions.capacity() * std::mem::size_of::<Arc<Index>>();
+        MemUsage {
+            index: self.memory_usage(),
+            maps,
+            names,
+        }
+    }
+
+    pub fn serialized_length(&self) -> usize {
+        self.all_partitions()
+            .iter()
+            .map(|idx| idx.serialized_length())
+            .sum()
+    }
+
+    pub fn change_expansion_search(&self, ef_search: usize) -> Result<(), String> {
+        for idx in self.all_partitions() {
+            idx.change_expansion_search(ef_search)
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    // search all partitions of the index
+    pub fn search(&self, query: &[f64], k: usize) -> Result<Matches, String> {
+        search_partitions(&self.all_partitions(), query, k)
+    }
+}
+
+// partition index of the vector id in n partitions
+pub fn partition_of(id: u64, n: usize) -> usize {
+    (xxh3_64(&id.to_le_bytes()) % n as u64) as usize
+}
+
+// search top K of each partition, then merge the top K by distance.
+// note: partitions are searched one by one on the calling thread (a search worker),
+// threads aren't spawned per search, concurrent searches run on the worker pool.
+pub fn search_partitions(
+    partitions: &[Arc<Index>],
+    query: &[f64],
+    k: usize,
+) -> Result<Matches, String> {
+    search_partitions_profile(partitions, query, k).map(|(matches, _)| matches)
+}
+
+// SearchProfile
+// time spent by the search of each partition and the merge of their top K
+#[derive(Debug, Default)]
+pub struct SearchProfile {
+    pub partitions_usec: Vec<u128>,
+    pub merge_usec: u128,
+}
+
+// search_partitions with the time spent of each partition
+pub fn search_partitions_profile(
+    partitions: &[Arc<Index>],
+    query: &[f64],
+    k: usize,
+) -> Result<(Matches, SearchProfile), String> {
+    let timed_search = |idx: &Index| {
+        let start = Instant::now();
+        let res = idx.search(query, k).map_err(|e| e.to_string());
+        (res, start.elapsed().as_micros())
+    };
+
+    let mut profile = SearchProfile::default();
+    if partitions.len() == 1 {
+        let (res, usec) = timed_search(&partitions[0]);
+        profile.partitions_usec.push(usec);
+        return res.map(|matches| (matches, profile));
+    }
+
+    let results = partitions
+        .iter()
+        .map(|idx| timed_search(idx))
+        .collect::<Vec<(Result<Matches, String>, u128)>>();
+
+    let start = Instant::now();
+    let mut merged: Vec<(u64, f32)> = Vec::new();
+    for (res, usec) in results {
+        let matches = res?;
+        merged.extend(matches.keys.into_iter().zip(matches.distances));
+        profile.partitions_usec.push(usec);
+    }
+    merged.sort_by(|a, b| a.1.total_cmp(&b.1));
+    merged.truncate(k);
+    let (keys, distances) = merged.into_iter().unzip();
+    profile.merge_usec = start.elapsed().as_micros();
+    Ok((Matches { keys, distances }, profile))
+}
+
+// new usearch index with the index options, the capacity is reserved
+pub fn new_usearch_index(opts: &IndexOpts, cap: usize) -> Result<Index, String> {
+    let idx = Index::new(&opts.clone().into()).map_err(|e| e.to_string())?;
+    idx.reserve(cap).map_err(|e| e.to_string())?;
+    Ok(idx)
+}
+
+// copy the usearch index graph by serialization
+fn copy_usearch_index(src: &Index, opts: &IndexOpts, name: &str) -> Result<Index, String> {
+    let mut buffer = vec![0_u8; src.serialized_length()];
+    src.save_to_buffer(&mut buffer)
+        .map_err(|e| format!("save usearch index {} to buffer err {}", name, e))?;
+
+    let idx = Index::new(&opts.clone().into())
+        .map_err(|e| format!("new usearch index {} err {}", name, e))?;
+    idx.load_from_buffer(&buffer)
+        .map_err(|e| format!("load usearch index {} from buffer err {}", name, e))?;
+    idx.reserve(src.capacity())
+        .map_err(|e| format!("usearch index {} reserve err {}", name, e))?;
+    Ok(idx)
+}
+
+// node names are prefixed with the index key name, e.g. usearch.idx0.n1
+pub fn rename_node(node_name: &str, old_index_name: &str, new_index_name: &str) -> String {
+    match node_name.strip_prefix(old_index_name) {
+        Some(short_name) if short_name.starts_with('.') => {
+            format!("{}{}", new_index_name, short_name)
+        }
+        _ => node_name.to_string(),
+    }
+}
+
+// MemUsage
+// approximate memory usage of an index in bytes by part
+#[derive(Default, Debug)]
+pub struct MemUsage {
+    pub index: usize, // usearch partitions with the graph and vectors
+    pub maps: usize,  // node id/name maps
+    pub names: usize, // node names, index name, file path and options
+}
+
+impl MemUsage {
+    pub fn total(&self) -> usize {
+        self.index + self.maps + self.names
+    }
+}
+
+// heap bytes of the strings, sample_size > 0 estimates them by the first sample_size strings
+fn strings_mem_usage<'a>(
+    strings: impl ExactSizeIterator<Item = &'a String>,
+    sample_size: usize,
+) -> usize {
+    let count = strings.len();
+    let sampled = match sample_size {
+        0 => count,
+        n => n.min(count),
+    };
+    if sampled == 0 {
+        return 0;
+    }
+    strings.take(sampled).map(|s| s.capacity()).sum::<usize>() * count / sampled
 }
 
 impl fmt::Debug for IndexRedis {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let idx = self.index.as_ref().unwrap();
         write!(
             f,
             "name: {}, \
@@ -160,6 +459,7 @@ impl fmt::Debug for IndexRedis {
             index_size: {}, \
             index_capacity: {}, \
             index_mem_usage: {}, \
+            partitions: {}, \
             ",
             self.name,
             self.index_opts.dimensions,
@@ -169,16 +469,22 @@ impl fmt::Debug for IndexRedis {
             self.index_opts.expansion_add,
             self.index_opts.expansion_search,
             self.serialization_file_path,
-            idx.serialized_length(),
-            idx.size(),
-            idx.capacity(),
-            idx.memory_usage(),
+            self.serialized_length(),
+            self.size(),
+            self.capacity(),
+            self.memory_usage(),
+            self.partitions.len() + 1,
         )
     }
 }
 
 impl From<IndexRedis> for RedisValue {
     fn from(index: IndexRedis) -> Self {
+        let serialized_length = index.serialized_length();
+        let size = index.size();
+        let capacity = index.capacity();
+        let mem_usage = index.mem_usage(0);
+        let partitions = index.partitions.len() + 1;
         let mut reply: Vec<RedisValue> = Vec::new();
 
         reply.push("name".into());
@@ -202,18 +508,42 @@ impl From<IndexRedis> for RedisValue {
         reply.push("expansion_search".into());
         reply.push(index.index_opts.expansion_search.into());
 
+        reply.push("model".into());
+        reply.push(
+            index
+                .index_opts
+                .model
+                .clone()
+                .map_or(RedisValue::Null, |m| m.into()),
+        );
+        reply.push("version".into());
+        reply.push(
+            index
+                .index_opts
+                .version
+                .clone()
+                .map_or(RedisValue::Null, |v| v.into()),
+        );
+
         reply.push("serialization_file_path".into());
         reply.push(index.serialization_file_path.as_str().into());
 
-        let idx = index.index.unwrap();
         reply.push("serialized_length".into());
-        reply.push(idx.serialized_length().into());
+        reply.push(serialized_length.into());
         reply.push("index_size".into());
-        reply.push(idx.size().into());
+        reply.push(size.into());
         reply.push("index_capacity".into());
-        reply.push(idx.capacity().into());
+        reply.push(capacity.into());
         reply.push("index_mem_usage".into());
-        reply.push(idx.memory_usage().into());
+        reply.push(mem_usage.index.into());
+        reply.push("maps_mem_usage".into());
+        reply.push(mem_usage.maps.into());
+        reply.push("names_mem_usage".into());
+        reply.push(mem_usage.names.into());
+        reply.push("mem_usage".into());
+        reply.push(mem_usage.total().into());
+        reply.push("partitions".into());
+        reply.push(partitions.into());
 
         reply.into()
     }
@@ -231,24 +561,25 @@ pub static USEARCH_INDEX_REDIS_TYPE: RedisType = RedisType::new(
         free: Some(free_index),
 
         // Currently unused by Redis
-        mem_usage: Some(mem_usage_index),
-        digest: None,
+        mem_usage: None,
+        digest: Some(digest_index),
 
         // Aux data
-        aux_load: None,
-        aux_save: None,
+        // index aliases
+        aux_load: Some(crate::alias::aux_load),
+        aux_save: Some(crate::alias::aux_save),
         aux_save2: None,
-        aux_save_triggers: 0,
+        aux_save_triggers: raw::REDISMODULE_AUX_BEFORE_RDB as i32,
 
-        copy: Some(copy_index),
-        free_effort: None,
+        copy: None,
+        free_effort: Some(free_effort_index),
         unlink: None,
-        defrag: None,
+        defrag: Some(defrag_index),
 
-        copy2: None,
+        copy2: Some(copy_index),
         free_effort2: None,
-        mem_usage2: None,
-        unlink2: None,
+        mem_usage2: Some(mem_usage_index),
+        unlink2: Some(unlink_index),
     },
 );
 
@@ -257,112 +588,227 @@ unsafe extern "C" fn save_index(rdb: *mut raw::RedisModuleIO, value: *mut c_void
     // let index = Box::from_raw(value as *mut IndexRedis);
     let index = unsafe { &*value.cast::<IndexRedis>() };
 
-    let name_cstring = CString::new(index.name.as_str()).unwrap();
-    raw::save_string(rdb, name_cstring.to_str().unwrap());
-
-    let opts_serialized_json = serde_json::to_string(&index.index_opts).unwrap();
-    let opts_cjson = CString::new(opts_serialized_json).unwrap();
-    raw::save_string(rdb, opts_cjson.to_str().unwrap());
+    // serde_json::to_string of the opts and string maps can't fail
+    raw::save_string(rdb, index.name.as_str());
+    raw::save_string(
+        rdb,
+        &serde_json::to_string(&index.index_opts).unwrap_or_default(),
+    );
+    raw::save_string(
+        rdb,
+        &serde_json::to_string(&index.node_id_name_map).unwrap_or_default(),
+    );
+    raw::save_string(
+        rdb,
+        &serde_json::to_string(&index.node_name_id_map).unwrap_or_default(),
+    );
+    raw::save_string(
+        rdb,
+        &serde_json::to_string(&index.vector_ids).unwrap_or_default(),
+    );
+    raw::save_string(rdb, index.serialization_file_path.as_str());
+    raw::save_string(rdb, index.capacity().to_string().as_str());
+
+    // partitions are saved in the rdb, the partition capacity and serialized buffer,
+    // the rdb save can't be failed by the module, so save errors are logged and the partition is empty,
+    // the nodes of an empty partition are dropped on load.
+    let partitions = index.all_partitions();
+    raw::RedisModule_SaveUnsigned.unwrap()(rdb, partitions.len() as u64);
+    if partitions.len() > 1 {
+        for (i, partition) in partitions.iter().enumerate() {
+            raw::RedisModule_SaveUnsigned.unwrap()(rdb, partition.capacity() as u64);
+            let mut buffer = vec![0_u8; partition.serialized_length()];
+            if let Err(e) = partition.save_to_buffer(&mut buffer) {
+                logging::log_warning(format!(
+                    "save Index: {} partition {} to buffer err {}",
+                    index.name, i, e
+                ));
+                buffer.clear();
+            }
+            raw::save_slice(rdb, &buffer);
+        }
+        return;
+    }
 
-    let id_name_serialized_json = serde_json::to_string(&index.node_id_name_map).unwrap();
-    let id_name_cjson = CString::new(id_name_serialized_json).unwrap();
-    raw::save_string(rdb, id_name_cjson.to_str().unwrap());
+    match index.index.as_ref() {
+        Some(idx) => {
+            if let Err(e) = storage::save(idx, index.serialization_file_path.as_str()) {
+                logging::log_warning(format!(
+                    "save Index: {} to {} err {}",
+                    index.name, index.serialization_file_path, e
+                ));
+            }
+        }
+        None => logging::log_warning(format!("save Index: {} err un init", index.name)),
+    }
+}
 
-    let name_id_serialized_json = serde_json::to_string(&index.node_name_id_map).unwrap();
-    let name_id_cjson = CString::new(name_id_serialized_json).unwrap();
-    raw::save_string(rdb, name_id_cjson.to_str().unwrap());
+unsafe extern "C" fn load_index(rdb: *mut raw::RedisModuleIO, encver: c_int) -> *mut c_void {
+    if encver > INDEX_VERSION {
+        logging::log_warning(format!(
+            "load usearch index err: rdb encver {} is newer than module encver {}, upgrade the module",
+            encver, INDEX_VERSION
+        ));
+        return ptr::null_mut();
+    }
+    match load_index_encver(rdb, encver) {
+        Ok(index) => {
+            logging::log_debug(format!("load Usearch Index {:?}", index));
+            Box::into_raw(Box::new(index)) as *mut c_void
+        }
+        Err(e) => {
+            logging::log_warning(format!("load usearch index (encver {}) err {}", encver, e));
+            ptr::null_mut()
+        }
+    }
+}
 
-    let path_cstring = CString::new(index.serialization_file_path.as_str()).unwrap();
-    raw::save_string(rdb, path_cstring.to_str().unwrap());
+// load the index fields saved by encver, then upgrade them to the current version
+unsafe fn load_index_encver(rdb: *mut raw::RedisModuleIO, encver: c_int) -> RdbResult<IndexRedis> {
+    let mut index = IndexRedis::default();
+    index.name = rdb::load_string(rdb)?;
+    index.index_opts = rdb::load_json(rdb, "index opts")?;
+    if index.index_opts.dimensions == 0 {
+        return Err(format!("Index: {} dimensions is 0", index.name));
+    }
+    index.node_id_name_map = rdb::load_json(rdb, "node id name map")?;
+    index.node_name_id_map = rdb::load_json(rdb, "node name id map")?;
+    match encver {
+        // upgrade encver 0, 1: the vector ids without node name are kept in the id map with empty name
+        0 | 1 => {
+            index.vector_ids = index
+                .node_id_name_map
+                .iter()
+                .filter(|(_, node_name)| node_name.is_empty())
+                .map(|(id, _)| *id)
+                .collect();
+            index
+                .node_id_name_map
+                .retain(|_, node_name| !node_name.is_empty());
+        }
+        _ => index.vector_ids = rdb::load_json(rdb, "vector ids")?,
+    }
+    if index.node_id_name_map.len() != index.node_name_id_map.len() {
+        return Err(format!(
+            "Index: {} node id name map len {} != node name id map len {}",
+            index.name,
+            index.node_id_name_map.len(),
+            index.node_name_id_map.len()
+        ));
+    }
+    index.serialization_file_path = rdb::load_string(rdb)?;
+    let cap = rdb::load_string(rdb)?;
+    let cap: usize = cap
+        .parse()
+        .map_err(|e| format!("Index: {} cap {} err {}", index.name, cap, e))?;
+
+    let partitions = match encver {
+        // upgrade encver 0: no partitions, the index is saved in the serialization file
+        0 => 1,
+        // encver 1: partition count, the partitions are saved in the rdb if count > 1
+        _ => rdb::load_len(rdb)?,
+    };
+    if partitions == 0 || partitions > crate::MAX_INDEX_PARTITIONS {
+        return Err(format!(
+            "Index: {} partitions {} not in [1, {}]",
+            index.name,
+            partitions,
+            crate::MAX_INDEX_PARTITIONS
+        ));
+    }
+    if partitions == 1 {
+        let idx = new_usearch_index(&index.index_opts, 0)?;
+        // the serialization file maybe lost, load an empty index with a warning
+        if let Err(e) = idx.load(index.serialization_file_path.as_str()) {
+            logging::log_warning(format!(
+                "load Index: {} from file {} err {}",
+                index.name, index.serialization_file_path, e
+            ));
+            drop_lost_nodes(&mut index, 1, &[0]);
+        }
+        reserve_loaded(&index.name, &idx, cap);
+        index.index = Some(Arc::new(idx));
+        return Ok(index);
+    }
 
-    let idx = index.index.as_ref().unwrap();
-    let cap_cstring = CString::new(idx.capacity().to_string().as_str()).unwrap();
-    raw::save_string(rdb, cap_cstring.to_str().unwrap());
+    let mut lost = Vec::new();
+    index.partitions = rdb::with_capacity(partitions - 1);
+    for i in 0..partitions {
+        let cap = rdb::load_unsigned(rdb)? as usize;
+        let buffer = rdb::load_buffer(rdb)?;
+        let idx = new_usearch_index(&index.index_opts, 0)?;
+        // a partition which failed to save is saved as an empty buffer
+        if buffer.is_empty() {
+            logging::log_warning(format!(
+                "load Index: {} partition {} is empty",
+                index.name, i
+            ));
+            lost.push(i);
+        } else {
+            idx.load_from_buffer(buffer.as_ref())
+                .map_err(|e| format!("load Index: {} partition {} err {}", index.name, i, e))?;
+        }
+        reserve_loaded(&index.name, &idx, cap);
+        match i {
+            0 => index.index = Some(Arc::new(idx)),
+            _ => index.partitions.push(Arc::new(idx)),
+        }
+    }
+    drop_lost_nodes(&mut index, partitions, &lost);
+    Ok(index)
+}
 
-    // match options Some/None
-    if index.index.is_some() {
-        let _ = idx
-            .save(index.serialization_file_path.as_str())
-            .is_err_and(|e| {
-                panic!(
-                    "save usearch index to {} fail! err {}",
-                    index.serialization_file_path,
-                    e.to_string()
-                )
-            });
-    } else {
-        panic!("usearch index un init");
+// drop_lost_nodes
+// the nodes of the lost partitions are removed from the node maps,
+// so the index doesn't keep node names and ids without vectors.
+fn drop_lost_nodes(index: &mut IndexRedis, partitions: usize, lost: &[usize]) {
+    if lost.is_empty() {
+        return;
     }
+    let n = index.node_count();
+    let is_kept = |id: &usize| !lost.contains(&partition_of(*id as u64, partitions));
+    index.node_id_name_map.retain(|id, _| is_kept(id));
+    index.vector_ids.retain(is_kept);
+    let ids = &index.node_id_name_map;
+    index.node_name_id_map.retain(|_, id| ids.contains_key(id));
+    logging::log_warning(format!(
+        "load Index: {} drop {} nodes of the lost partitions {:?}",
+        index.name,
+        n - index.node_count(),
+        lost
+    ));
 }
 
-unsafe extern "C" fn load_index(rdb: *mut raw::RedisModuleIO, encver: c_int) -> *mut c_void {
-    match encver {
-        0 => {
-            let mut index = Box::new(IndexRedis::default());
-            index.name = RedisString::from_ptr(raw::RedisModule_LoadString.unwrap()(rdb))
-                .unwrap()
-                .to_owned();
-
-            let index_opts_json = RedisString::from_ptr(raw::RedisModule_LoadString.unwrap()(rdb))
-                .unwrap()
-                .to_owned();
-            index.index_opts = serde_json::from_str(&index_opts_json).unwrap();
-
-            let id_name_json = RedisString::from_ptr(raw::RedisModule_LoadString.unwrap()(rdb))
-                .unwrap()
-                .to_owned();
-            if id_name_json.len() > 0 {
-                index.node_id_name_map = serde_json::from_str(&id_name_json).unwrap();
-            }
+fn reserve_loaded(name: &str, idx: &Index, cap: usize) {
+    if let Err(e) = idx.reserve(cap) {
+        logging::log_warning(format!("Index: {} reserve cap {} err {}", name, cap, e));
+    }
+}
 
-            let name_id_json = RedisString::from_ptr(raw::RedisModule_LoadString.unwrap()(rdb))
-                .unwrap()
-                .to_owned();
-            if name_id_json.len() > 0 {
-                index.node_name_id_map = serde_json::from_str(&name_id_json).unwrap();
+// digest of the options, partitions and nodes with vectors in id order,
+// the usearch graph links depend on the random levels of insertion, so they aren't digested.
+unsafe extern "C" fn digest_index(md: *mut raw::RedisModuleDigest, value: *mut c_void) {
+    let index = unsafe { &*value.cast::<IndexRedis>() };
+    let digest = Digest::new(md);
+
+    // the serialization file path depends on the instance dir, it isn't digested
+    digest.add_str(&index.name);
+    digest.add_str(&serde_json::to_string(&index.index_opts).unwrap_or_default());
+    digest.add_u64(index.all_partitions().len() as u64);
+    digest.end_sequence();
+
+    let mut vector = vec![0.0 as f64; index.index_opts.dimensions];
+    for (id, node_name) in index.nodes(0) {
+        digest.add_u64(id as u64);
+        digest.add_str(node_name);
+        if let Ok(n) = index.partition(id as u64).get(id as u64, &mut vector) {
+            if n > 0 {
+                for v in vector.iter() {
+                    digest.add_bytes(&v.to_le_bytes());
+                }
             }
-
-            index.index = Some(Arc::new(
-                Index::new(&index.index_opts.clone().into()).unwrap(),
-            ));
-
-            index.serialization_file_path =
-                RedisString::from_ptr(raw::RedisModule_LoadString.unwrap()(rdb))
-                    .unwrap()
-                    .to_owned();
-
-            let cap = RedisString::from_ptr(raw::RedisModule_LoadString.unwrap()(rdb))
-                .unwrap()
-                .to_owned();
-
-            let idx = index
-                .index
-                .as_ref()
-                .unwrap_or_else(|| panic!("usearch index un init"));
-            idx.load(index.serialization_file_path.as_str())
-                .unwrap_or_else(|e| {
-                    //panic!(
-                    println!(
-                        "load fail! from file {} err {}",
-                        index.serialization_file_path,
-                        e.to_string()
-                    )
-                });
-
-            idx.reserve(cap.parse().unwrap()).unwrap_or_else(|e| {
-                println!(
-                    "index {} reserve cap {} err {}",
-                    index.name,
-                    cap,
-                    e.to_string()
-                )
-            });
-
-            println!("load Usearch Index {:?}", index);
-            let index: *mut c_void = Box::into_raw(index) as *mut c_void;
-            index
         }
-        _ => ptr::null_mut() as *mut c_void,
+        digest.end_sequence();
     }
 }
 
@@ -374,20 +820,101 @@ unsafe extern "C" fn free_index(value: *mut c_void) {
     drop(Box::from_raw(value as *mut IndexRedis));
 }
 
-unsafe extern "C" fn mem_usage_index(value: *const c_void) -> usize {
-    let index = Box::from_raw(value as *mut IndexRedis);
-    index.index.as_ref().unwrap().memory_usage()
+// UNLINK (and DEL with lazyfree-lazy-user-del) frees the index in the lazyfree thread
+// if the effort is greater than the redis LAZYFREE_THRESHOLD, the effort is the vector count.
+unsafe extern "C" fn free_effort_index(
+    _key: *mut raw::RedisModuleString,
+    value: *const c_void,
+) -> usize {
+    let index = unsafe { &*value.cast::<IndexRedis>() };
+    index.node_count().max(1)
 }
 
-//#[allow(unused)]
+// the index value is removed from the keyspace (deleted or overwritten) before it's freed maybe in
+// the lazyfree thread, unregister it on the main thread.
+unsafe extern "C" fn unlink_index(ctx: *mut raw::RedisModuleKeyOptCtx, _value: *const c_void) {
+    let db = raw::RedisModule_GetDbIdFromOptCtx.unwrap()(ctx);
+    let key = raw::RedisModule_GetKeyNameFromOptCtx.unwrap()(ctx);
+    if let Ok(name) = RedisString::from_ptr(key) {
+        crate::registry::remove(db, name);
+    }
+}
+
+// activedefrag moves the value and node names, the cursor is the next vector id of the node maps.
+// the usearch index buffers are allocated by usearch, they aren't defragged.
+// note: workers only hold the partitions, node maps are used on the main thread
+unsafe extern "C" fn defrag_index(
+    ctx: *mut raw::RedisModuleDefragCtx,
+    _key: *mut raw::RedisModuleString,
+    value: *mut *mut c_void,
+) -> c_int {
+    let defrag = Defrag::new(ctx);
+    let cursor = match defrag.cursor() {
+        Some(cursor) => cursor as usize,
+        None => {
+            defrag.value(value);
+            let index = unsafe { &mut *(*value).cast::<IndexRedis>() };
+            defrag.string(&mut index.name);
+            defrag.string(&mut index.serialization_file_path);
+            defrag.vec(&mut index.partitions);
+            0
+        }
+    };
+
+    let index = unsafe { &mut *(*value).cast::<IndexRedis>() };
+    for (id, name) in index.node_id_name_map.range_mut(cursor..) {
+        defrag.string(name);
+        // hash map keys can't be changed in place, the moved key is put back, the table keeps its capacity
+        if let Some((mut key, id)) = index.node_name_id_map.remove_entry(name.as_str()) {
+            defrag.string(&mut key);
+            index.node_name_id_map.insert(key, id);
+        }
+        if defrag.should_stop() {
+            defrag.set_cursor(*id as u64 + 1);
+            return 1;
+        }
+    }
+    0
+}
+
+// MEMORY USAGE key [SAMPLES count]
+unsafe extern "C" fn mem_usage_index(
+    _ctx: *mut raw::RedisModuleKeyOptCtx,
+    value: *const c_void,
+    sample_size: usize,
+) -> usize {
+    let index = unsafe { &*value.cast::<IndexRedis>() };
+    index.mem_usage(sample_size).total()
+}
+
+// COPY src dst [DB db] [REPLACE]
+// copy2 gives the target key name and db, the copied index is stored in its own serialization file.
 unsafe extern "C" fn copy_index(
-    _: *mut raw::RedisModuleString,
-    _: *mut raw::RedisModuleString,
+    ctx: *mut raw::RedisModuleKeyOptCtx,
     value: *const c_void,
 ) -> *mut c_void {
-    let idx = unsafe { &*value.cast::<IndexRedis>() };
-    let value = idx.clone();
-    Box::into_raw(Box::new(value)).cast::<c_void>()
+    let index = unsafe { &*value.cast::<IndexRedis>() };
+    let to_db = raw::RedisModule_GetToDbIdFromOptCtx.unwrap()(ctx);
+    let to_key = raw::RedisModule_GetToKeyNameFromOptCtx.unwrap()(ctx);
+    let to_name = match RedisString::from_ptr(to_key) {
+        Ok(name) => name.to_owned(),
+        Err(e) => {
+            logging::log_warning(format!("copy Index: {} err {}", index.name, e));
+            return ptr::null_mut();
+        }
+    };
+
+    match index.deep_copy(&to_name, crate::index_file_path(to_db, &to_name)) {
+        Ok(copied) => Box::into_raw(Box::new(copied)).cast::<c_void>(),
+        Err(e) => {
+            // null means copy fail, COPY reply error
+            logging::log_warning(format!(
+                "copy Index: {} to {} err {}",
+                index.name, to_name, e
+            ));
+            ptr::null_mut()
+        }
+    }
 }
 
 #[derive(Default)]
@@ -17,7 +17,7 @@ cd RedisXANN && source "$HOME/.cargo/env" && \
   && cd -
 
 redis-server --daemonize yes \
-  --loadmodule RedisXANN/target/release/libredisxann_usearch.so is_remove_serialized_file 1 \
+  --loadmodule RedisXANN/target/release/libredisxann_usearch.so remove_serialized_file yes \
   --port 6666 \
   --dbfilename dump.6666.rdb
 